@@ -0,0 +1,730 @@
+//! Issue commands to HAProxy from asynchronous code using tokio.
+//!
+//! [`AsyncConnection`] provides the same commands as [`Connection`], returning futures instead of
+//! blocking. Both are built on [`protocol`], so commands are encoded and responses parsed
+//! identically: each command is sent in one write, and its response is read until HAProxy closes
+//! the connection.
+//!
+//! HAProxy's interactive prompt mode is not supported by [`AsyncConnection`]; queue commands in a
+//! [`Batch`] to send several at once instead.
+//!
+//! # Examples
+//! ```no_run
+//! use haptik::async_connection::AsyncConnectionBuilder;
+//! use haptik::UnixSocketBuilder;
+//!
+//! #[tokio::main(flavor = "current_thread")]
+//! async fn main() {
+//!     let socket_builder = UnixSocketBuilder::default();
+//!     let connection = socket_builder.connect_async().await.expect("Failed to connect");
+//!     let errors = connection.errors().await.expect("Failed to query error count");
+//!     println!("Total errors across all backends: {}", errors);
+//! }
+//! ```
+
+use std::future::Future;
+use std::io;
+use std::net::IpAddr;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpStream, UnixStream};
+
+use crate::batch::{Batch, BatchResponses};
+use crate::commands;
+use crate::connection::{TcpSocketBuilder, Timeouts, UnixSocketBuilder};
+use crate::errors::Error;
+use crate::models;
+use crate::protocol::{self, Command, Response};
+use crate::requests::{
+    AgentState, BackendId, EntryRef, ErrorFlag, HealthState, RateLimit, ServerId, ServerSpec,
+    ServerState, StatFilter, TableFilter, Weight,
+};
+use crate::responses::{self, Acl};
+#[cfg(doc)]
+use crate::Connection;
+
+/// Support asynchronous connections to HAProxy via Unix sockets and TCP sockets.
+///
+/// This is implemented by the same builders as [`ConnectionBuilder`](crate::ConnectionBuilder),
+/// and the timeouts configured on them apply in the same way. Timeouts require a tokio runtime with
+/// the time driver enabled.
+pub trait AsyncConnectionBuilder {
+    type Connection;
+
+    /// Create a new connection to HAProxy.
+    fn connect_async(&self) -> impl Future<Output = Result<Self::Connection, io::Error>> + Send;
+}
+
+impl AsyncConnectionBuilder for UnixSocketBuilder {
+    type Connection = AsyncConnection<UnixStream>;
+
+    async fn connect_async(&self) -> Result<Self::Connection, io::Error> {
+        let socket = with_timeout(self.timeouts.connect, UnixStream::connect(&self.path)).await?;
+
+        Ok(AsyncConnection {
+            socket,
+            timeouts: self.timeouts,
+        })
+    }
+}
+
+impl AsyncConnectionBuilder for TcpSocketBuilder {
+    type Connection = AsyncConnection<TcpStream>;
+
+    async fn connect_async(&self) -> Result<Self::Connection, io::Error> {
+        let socket = with_timeout(self.timeouts.connect, TcpStream::connect(self.addr)).await?;
+
+        Ok(AsyncConnection {
+            socket,
+            timeouts: self.timeouts,
+        })
+    }
+}
+
+/// An asynchronous connection to HAProxy.
+///
+/// As with [`Connection`], methods take `self` because HAProxy closes the connection after each
+/// command.
+#[derive(Debug)]
+pub struct AsyncConnection<T> {
+    socket: T,
+    timeouts: Timeouts,
+}
+
+impl<T: AsyncRead + AsyncWrite + Unpin> AsyncConnection<T> {
+    /// Asynchronous version of [`Connection::execute`].
+    pub async fn execute<C: Command>(
+        mut self,
+        command: &C,
+    ) -> Result<<C::Response as Response>::Output, Error> {
+        let mut response = command.response();
+        self.exchange(&command.to_bytes(), |data| {
+            response.feed(data);
+        })
+        .await?;
+        response.finish()
+    }
+
+    /// Asynchronous version of [`Connection::batch`].
+    pub async fn batch(mut self, batch: &Batch) -> Result<BatchResponses, Error> {
+        if batch.is_empty() {
+            return Ok(BatchResponses::default());
+        }
+
+        let mut request = batch.line().to_vec();
+        commands::end(&mut request)?;
+
+        let mut output = Vec::new();
+        self.exchange(&request, |data| output.extend_from_slice(data))
+            .await?;
+        BatchResponses::split(&output)
+    }
+
+    /// Send `request` and pass each chunk of the response to `feed` until HAProxy closes the
+    /// connection.
+    async fn exchange<F: FnMut(&[u8])>(
+        &mut self,
+        request: &[u8],
+        mut feed: F,
+    ) -> Result<(), Error> {
+        let timeouts = self.timeouts;
+        let socket = &mut self.socket;
+
+        let exchange = async move {
+            with_timeout(timeouts.write, socket.write_all(request)).await?;
+
+            let mut buf = [0; 4096];
+            loop {
+                let len = with_timeout(timeouts.read, socket.read(&mut buf)).await?;
+                if len == 0 {
+                    return Ok(());
+                }
+                feed(&buf[..len]);
+            }
+        };
+        Ok(with_timeout(timeouts.deadline, exchange).await?)
+    }
+
+    /// Asynchronous version of [`Connection::acl_add`].
+    pub async fn acl_add<E: ToString>(self, id: models::AclId, value: E) -> Result<(), Error> {
+        self.execute(&protocol::acl_add(id, value)?).await
+    }
+
+    /// Asynchronous version of [`Connection::acl_add_version`].
+    pub async fn acl_add_version<E: ToString>(
+        self,
+        id: models::AclId,
+        version: u32,
+        value: E,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::acl_add_version(id, version, value)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::acl_clear`].
+    pub async fn acl_clear(self, id: models::AclId) -> Result<(), Error> {
+        self.execute(&protocol::acl_clear(id)?).await
+    }
+
+    /// Asynchronous version of [`Connection::acl_clear_version`].
+    pub async fn acl_clear_version(self, id: models::AclId, version: u32) -> Result<(), Error> {
+        self.execute(&protocol::acl_clear_version(id, version)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::acl_commit`].
+    pub async fn acl_commit(self, id: models::AclId, version: u32) -> Result<(), Error> {
+        self.execute(&protocol::acl_commit(id, version)?).await
+    }
+
+    /// Asynchronous version of [`Connection::acl_data`].
+    pub async fn acl_data<E: FromStr>(
+        self,
+        id: models::AclId,
+    ) -> Result<Vec<models::AclEntry<E>>, Error> {
+        self.execute(&protocol::acl_data(id)?).await
+    }
+
+    /// Asynchronous version of [`Connection::acl_del`].
+    pub async fn acl_del(self, id: models::AclId, entry: EntryRef<'_>) -> Result<(), Error> {
+        self.execute(&protocol::acl_del(id, entry)?).await
+    }
+
+    /// Asynchronous version of [`Connection::acl_get`].
+    pub async fn acl_get<E: ToString>(
+        self,
+        id: models::AclId,
+        value: E,
+    ) -> Result<responses::AclMatch, Error> {
+        self.execute(&protocol::acl_get(id, value)?).await
+    }
+
+    /// Asynchronous version of [`Connection::acl_list`].
+    pub async fn acl_list(self) -> Result<Vec<Acl>, Error> {
+        self.execute(&protocol::acl_list()).await
+    }
+
+    /// Asynchronous version of [`Connection::acl_prepare`].
+    pub async fn acl_prepare(self, id: models::AclId) -> Result<u32, Error> {
+        self.execute(&protocol::acl_prepare(id)?).await
+    }
+
+    /// Asynchronous version of [`Connection::acl_resolve`].
+    pub async fn acl_resolve<P: AsRef<Path>>(self, path: P) -> Result<models::AclId, Error> {
+        let path = path.as_ref();
+        self.acl_list()
+            .await?
+            .into_iter()
+            .find(|acl| acl.reference.as_deref().map(Path::new) == Some(path))
+            .map(|acl| models::AclId::Id(acl.id))
+            .ok_or(Error::UnknownId)
+    }
+
+    /// Asynchronous version of [`Connection::map_add`].
+    pub async fn map_add<K: ToString, V: ToString>(
+        self,
+        id: models::MapId,
+        key: K,
+        value: V,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::map_add(id, key, value)?).await
+    }
+
+    /// Asynchronous version of [`Connection::map_add_version`].
+    pub async fn map_add_version<K: ToString, V: ToString>(
+        self,
+        id: models::MapId,
+        version: u32,
+        key: K,
+        value: V,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::map_add_version(id, version, key, value)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::map_clear`].
+    pub async fn map_clear(self, id: models::MapId) -> Result<(), Error> {
+        self.execute(&protocol::map_clear(id)?).await
+    }
+
+    /// Asynchronous version of [`Connection::map_clear_version`].
+    pub async fn map_clear_version(self, id: models::MapId, version: u32) -> Result<(), Error> {
+        self.execute(&protocol::map_clear_version(id, version)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::map_commit`].
+    pub async fn map_commit(self, id: models::MapId, version: u32) -> Result<(), Error> {
+        self.execute(&protocol::map_commit(id, version)?).await
+    }
+
+    /// Asynchronous version of [`Connection::map_entries`].
+    pub async fn map_entries<K: FromStr, V: FromStr>(
+        self,
+        id: models::MapId,
+    ) -> Result<Vec<models::MapEntry<K, V>>, Error> {
+        self.execute(&protocol::map_entries(id)?).await
+    }
+
+    /// Asynchronous version of [`Connection::map_del`].
+    pub async fn map_del(self, id: models::MapId, entry: EntryRef<'_>) -> Result<(), Error> {
+        self.execute(&protocol::map_del(id, entry)?).await
+    }
+
+    /// Asynchronous version of [`Connection::map_get`].
+    pub async fn map_get<K: ToString>(
+        self,
+        id: models::MapId,
+        key: K,
+    ) -> Result<responses::MapMatch, Error> {
+        self.execute(&protocol::map_get(id, key)?).await
+    }
+
+    /// Asynchronous version of [`Connection::map_list`].
+    pub async fn map_list(self) -> Result<Vec<responses::Map>, Error> {
+        self.execute(&protocol::map_list()).await
+    }
+
+    /// Asynchronous version of [`Connection::map_set`].
+    pub async fn map_set<V: ToString>(
+        self,
+        id: models::MapId,
+        entry: EntryRef<'_>,
+        value: V,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::map_set(id, entry, value)?).await
+    }
+
+    /// Asynchronous version of [`Connection::map_prepare`].
+    pub async fn map_prepare(self, id: models::MapId) -> Result<u32, Error> {
+        self.execute(&protocol::map_prepare(id)?).await
+    }
+
+    /// Asynchronous version of [`Connection::map_resolve`].
+    pub async fn map_resolve<P: AsRef<Path>>(self, path: P) -> Result<models::MapId, Error> {
+        let path = path.as_ref();
+        self.map_list()
+            .await?
+            .into_iter()
+            .find(|map| map.reference.as_deref().map(Path::new) == Some(path))
+            .map(|map| models::MapId::Id(map.id))
+            .ok_or(Error::UnknownId)
+    }
+
+    /// Asynchronous version of [`Connection::level`].
+    pub async fn level(self) -> Result<responses::Level, Error> {
+        self.execute(&protocol::level()).await
+    }
+
+    /// Asynchronous version of [`Connection::backends`].
+    pub async fn backends(self) -> Result<Vec<String>, Error> {
+        self.execute(&protocol::backends()).await
+    }
+
+    /// Asynchronous version of [`Connection::cli_sockets`].
+    pub async fn cli_sockets(self) -> Result<Vec<responses::CliSocket>, Error> {
+        self.execute(&protocol::cli_sockets()).await
+    }
+
+    /// Asynchronous version of [`Connection::errors`].
+    pub async fn errors(self) -> Result<u32, Error> {
+        self.execute(&protocol::errors()).await
+    }
+
+    /// Asynchronous version of [`Connection::errors_backend`].
+    pub async fn errors_backend(
+        self,
+        backend: BackendId<'_>,
+        error_type: ErrorFlag,
+    ) -> Result<u32, Error> {
+        self.execute(&protocol::errors_backend(backend, error_type)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::add_server`].
+    pub async fn add_server(
+        self,
+        backend: BackendId<'_>,
+        name: &str,
+        spec: &ServerSpec<'_>,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::add_server(backend, name, spec)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::del_server`].
+    pub async fn del_server(
+        self,
+        backend: BackendId<'_>,
+        server: ServerId<'_>,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::del_server(backend, server)?).await
+    }
+
+    /// Asynchronous version of [`Connection::disable_frontend`].
+    pub async fn disable_frontend(self, frontend: &str) -> Result<responses::StateChange, Error> {
+        self.execute(&protocol::disable_frontend(frontend)?).await
+    }
+
+    /// Asynchronous version of [`Connection::disable_health`].
+    pub async fn disable_health(
+        self,
+        backend: BackendId<'_>,
+        server: ServerId<'_>,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::disable_health(backend, server)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::disable_server`].
+    pub async fn disable_server(
+        self,
+        backend: BackendId<'_>,
+        server: ServerId<'_>,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::disable_server(backend, server)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::enable_frontend`].
+    pub async fn enable_frontend(self, frontend: &str) -> Result<responses::StateChange, Error> {
+        self.execute(&protocol::enable_frontend(frontend)?).await
+    }
+
+    /// Asynchronous version of [`Connection::enable_health`].
+    pub async fn enable_health(
+        self,
+        backend: BackendId<'_>,
+        server: ServerId<'_>,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::enable_health(backend, server)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::enable_server`].
+    pub async fn enable_server(
+        self,
+        backend: BackendId<'_>,
+        server: ServerId<'_>,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::enable_server(backend, server)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::info`].
+    pub async fn info(self) -> Result<responses::Info, Error> {
+        self.execute(&protocol::info()).await
+    }
+
+    /// Asynchronous version of [`Connection::info_typed`].
+    pub async fn info_typed(self) -> Result<Vec<responses::TypedInfo>, Error> {
+        self.execute(&protocol::info_typed()).await
+    }
+
+    /// Asynchronous version of [`Connection::info_json`].
+    #[cfg(feature = "json")]
+    pub async fn info_json(self) -> Result<responses::Tagged<responses::Info>, Error> {
+        self.execute(&protocol::info_json()).await
+    }
+
+    /// Asynchronous version of [`Connection::schema_json`].
+    #[cfg(feature = "json")]
+    pub async fn schema_json(self) -> Result<serde_json::Value, Error> {
+        self.execute(&protocol::schema_json()).await
+    }
+
+    /// Asynchronous version of [`Connection::servers_conn`].
+    pub async fn servers_conn(
+        self,
+        backend: Option<BackendId<'_>>,
+    ) -> Result<Vec<responses::ServerConnections>, Error> {
+        self.execute(&protocol::servers_conn(backend)?).await
+    }
+
+    /// Asynchronous version of [`Connection::servers_state`].
+    pub async fn servers_state(
+        self,
+        backend: Option<BackendId<'_>>,
+    ) -> Result<Vec<responses::ServerStateRow>, Error> {
+        self.execute(&protocol::servers_state(backend)?).await
+    }
+
+    /// Asynchronous version of [`Connection::sessions`].
+    pub async fn sessions(self) -> Result<Vec<responses::Session>, Error> {
+        self.execute(&protocol::sessions()).await
+    }
+
+    /// Asynchronous version of [`Connection::session`].
+    pub async fn session(self, ptr: u64) -> Result<String, Error> {
+        self.execute(&protocol::session(ptr)).await
+    }
+
+    /// Asynchronous version of [`Connection::set_maxconn_frontend`].
+    pub async fn set_maxconn_frontend(self, frontend: &str, maxconn: u32) -> Result<(), Error> {
+        self.execute(&protocol::set_maxconn_frontend(frontend, maxconn)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::set_maxconn_global`].
+    pub async fn set_maxconn_global(self, maxconn: u32) -> Result<(), Error> {
+        self.execute(&protocol::set_maxconn_global(maxconn)?).await
+    }
+
+    /// Asynchronous version of [`Connection::set_maxconn_server`].
+    pub async fn set_maxconn_server(
+        self,
+        backend: BackendId<'_>,
+        server: ServerId<'_>,
+        maxconn: u32,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::set_maxconn_server(backend, server, maxconn)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::set_rate_limit`].
+    pub async fn set_rate_limit(self, limit: RateLimit, value: u32) -> Result<(), Error> {
+        self.execute(&protocol::set_rate_limit(limit, value)?).await
+    }
+
+    /// Asynchronous version of [`Connection::set_server_addr`].
+    pub async fn set_server_addr(
+        self,
+        backend: BackendId<'_>,
+        server: ServerId<'_>,
+        addr: IpAddr,
+        port: Option<u16>,
+    ) -> Result<responses::AddrChange, Error> {
+        self.execute(&protocol::set_server_addr(backend, server, addr, port)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::set_server_agent`].
+    pub async fn set_server_agent(
+        self,
+        backend: BackendId<'_>,
+        server: ServerId<'_>,
+        state: AgentState,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::set_server_agent(backend, server, state)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::set_server_health`].
+    pub async fn set_server_health(
+        self,
+        backend: BackendId<'_>,
+        server: ServerId<'_>,
+        state: HealthState,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::set_server_health(backend, server, state)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::set_server_state`].
+    pub async fn set_server_state(
+        self,
+        backend: BackendId<'_>,
+        server: ServerId<'_>,
+        state: ServerState,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::set_server_state(backend, server, state)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::set_server_weight`].
+    pub async fn set_server_weight(
+        self,
+        backend: BackendId<'_>,
+        server: ServerId<'_>,
+        weight: Weight,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::set_server_weight(backend, server, weight)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::shutdown_frontend`].
+    pub async fn shutdown_frontend(self, frontend: &str) -> Result<responses::StateChange, Error> {
+        self.execute(&protocol::shutdown_frontend(frontend)?).await
+    }
+
+    /// Asynchronous version of [`Connection::table_clear`].
+    pub async fn table_clear(
+        self,
+        table: &str,
+        filter: Option<&TableFilter<'_>>,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::table_clear(table, filter)?).await
+    }
+
+    /// Asynchronous version of [`Connection::tables`].
+    pub async fn tables(self) -> Result<Vec<responses::StickTable>, Error> {
+        self.execute(&protocol::tables()).await
+    }
+
+    /// Asynchronous version of [`Connection::table_entries`].
+    pub async fn table_entries(
+        self,
+        table: &str,
+        filter: Option<&TableFilter<'_>>,
+    ) -> Result<Vec<responses::StickTableEntry>, Error> {
+        self.execute(&protocol::table_entries(table, filter)?).await
+    }
+
+    /// Asynchronous version of [`Connection::table_set`].
+    pub async fn table_set(
+        self,
+        table: &str,
+        key: &models::StickTableKey,
+        data: &[(&str, u64)],
+    ) -> Result<(), Error> {
+        self.execute(&protocol::table_set(table, key, data)?).await
+    }
+
+    /// Asynchronous version of [`Connection::shutdown_session`].
+    pub async fn shutdown_session(self, ptr: u64) -> Result<(), Error> {
+        self.execute(&protocol::shutdown_session(ptr)).await
+    }
+
+    /// Asynchronous version of [`Connection::shutdown_sessions_server`].
+    pub async fn shutdown_sessions_server(
+        self,
+        backend: BackendId<'_>,
+        server: ServerId<'_>,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::shutdown_sessions_server(backend, server)?)
+            .await
+    }
+
+    /// Asynchronous version of [`Connection::stats`].
+    pub async fn stats(self) -> Result<Vec<responses::StatRow>, Error> {
+        self.execute(&protocol::stats()).await
+    }
+
+    /// Asynchronous version of [`Connection::stats_typed`].
+    pub async fn stats_typed(self) -> Result<Vec<responses::TypedStat>, Error> {
+        self.execute(&protocol::stats_typed()).await
+    }
+
+    /// Asynchronous version of [`Connection::stats_json`].
+    #[cfg(feature = "json")]
+    pub async fn stats_json(self) -> Result<Vec<responses::Tagged<responses::StatRow>>, Error> {
+        self.execute(&protocol::stats_json()).await
+    }
+
+    /// Asynchronous version of [`Connection::stats_filtered`].
+    pub async fn stats_filtered(
+        self,
+        filter: StatFilter<'_>,
+    ) -> Result<Vec<responses::StatRow>, Error> {
+        self.execute(&protocol::stats_filtered(filter)?).await
+    }
+}
+
+/// Fail with `TimedOut` if `future` does not complete within `timeout`.
+async fn with_timeout<F, R>(timeout: Option<Duration>, future: F) -> io::Result<R>
+where
+    F: Future<Output = io::Result<R>>,
+{
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, future)
+            .await
+            .unwrap_or_else(|_| Err(io::Error::new(io::ErrorKind::TimedOut, "timed out"))),
+        None => future.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AclId, MapId};
+    use tokio::io::{AsyncBufReadExt, DuplexStream};
+
+    /// Answer a single command on the server half of a duplex stream, returning the command.
+    async fn respond(server: DuplexStream, response: &[u8]) -> String {
+        let mut server = tokio::io::BufReader::new(server);
+        let mut command = String::new();
+        server.read_line(&mut command).await.unwrap();
+        server.write_all(response).await.unwrap();
+        command
+    }
+
+    #[tokio::test]
+    async fn command_is_sent_and_response_parsed() {
+        let (client, server) = tokio::io::duplex(1024);
+        let connection = AsyncConnection {
+            socket: client,
+            timeouts: Timeouts::default(),
+        };
+
+        let (entries, command) = tokio::join!(
+            connection.map_entries::<String, String>(MapId::Id(1)),
+            respond(
+                server,
+                b"0x1 example.com server1\n0x2 example.org server2\n\n"
+            ),
+        );
+
+        assert_eq!(command, "show map #1\n");
+        assert_eq!(entries.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn errors_are_parsed() {
+        let (client, server) = tokio::io::duplex(1024);
+        let connection = AsyncConnection {
+            socket: client,
+            timeouts: Timeouts::default(),
+        };
+
+        let (result, command) = tokio::join!(
+            connection.acl_del(AclId::Id(0), EntryRef::Key("missing")),
+            respond(server, b"Key not found.\n\n"),
+        );
+
+        assert_eq!(command, "del acl #0 missing\n");
+        assert!(matches!(result, Err(Error::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn deadline_expires() {
+        let (client, _server) = tokio::io::duplex(1024);
+        let connection = AsyncConnection {
+            socket: client,
+            timeouts: Timeouts {
+                deadline: Some(Duration::from_millis(50)),
+                ..Timeouts::default()
+            },
+        };
+
+        assert!(matches!(connection.acl_list().await, Err(Error::Timeout)));
+    }
+
+    #[tokio::test]
+    async fn invalid_arguments_are_rejected_before_sending() {
+        let (client, _server) = tokio::io::duplex(1024);
+        let connection = AsyncConnection {
+            socket: client,
+            timeouts: Timeouts::default(),
+        };
+
+        assert!(matches!(
+            connection
+                .set_server_weight(BackendId::All, ServerId::Name("s1"), Weight::Absolute(1))
+                .await,
+            Err(Error::InvalidArgument)
+        ));
+    }
+
+    #[tokio::test]
+    async fn builders_implement_both_traits() {
+        // Both traits in scope must not make either method ambiguous.
+        use crate::ConnectionBuilder;
+
+        let builder = UnixSocketBuilder::new("/nonexistent/haproxy.sock");
+        assert!(builder.connect().is_err());
+        assert!(builder.connect_async().await.is_err());
+    }
+}
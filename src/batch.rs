@@ -0,0 +1,314 @@
+//! Send several commands to HAProxy in a single request.
+//!
+//! HAProxy accepts multiple commands on one line, separated by semicolons, and writes their
+//! responses one after another. A [`Batch`] queues commands, sends them with
+//! [`Connection::batch`](crate::Connection::batch), and splits the combined output back into one
+//! response per command. Each queued command returns a [`Pending`] handle which parses its own
+//! response into the same type the equivalent [`Connection`](crate::Connection) method returns.
+
+use std::io::{BufRead, BufReader, Cursor};
+use std::str::FromStr;
+
+use crate::errors::Error;
+use crate::models::{AclEntry, AclId, MapEntry, MapId};
+use crate::protocol::{self, Parser, Request};
+use crate::requests::EntryRef;
+use crate::responses::{Acl, AclMatch, Map, MapMatch};
+
+/// A queue of commands to send to HAProxy at once.
+///
+/// HAProxy reads the whole batch as one line, which must fit in its buffer (16 kB by default).
+/// Use [`Batch::encoded_len`] to split large updates across several batches.
+///
+/// Responses are separated by empty lines, so batches only support commands whose output never
+/// contains one.
+///
+/// # Examples
+/// ```no_run
+/// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+/// use haptik::batch::Batch;
+/// use haptik::models::MapId;
+///
+/// let mut batch = Batch::new();
+/// let added: Vec<_> = vec![("example.com", "server1"), ("example.org", "server2")]
+///     .into_iter()
+///     .map(|(key, value)| batch.map_add(MapId::Id(0), key, value))
+///     .collect::<Result<_, _>>()
+///     .expect("Invalid entry");
+///
+/// let socket_builder = UnixSocketBuilder::default();
+/// let connection = socket_builder.connect().expect("Failed to connect");
+/// let responses = connection.batch(&batch).expect("Failed to send batch");
+/// for pending in added.iter() {
+///     responses.get(pending).expect("Failed to add entry");
+/// }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Batch {
+    line: Vec<u8>,
+    count: usize,
+}
+
+/// A command queued in a [`Batch`], used to retrieve its response from [`BatchResponses`].
+#[derive(Clone, Copy, Debug)]
+pub struct Pending<R> {
+    index: usize,
+    parse: Parser<R>,
+}
+
+impl Batch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of commands queued.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Whether no commands are queued.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// The length in bytes of the line which will be sent to HAProxy.
+    pub fn encoded_len(&self) -> usize {
+        self.line.len()
+    }
+
+    /// The commands, separated by semicolons.
+    pub(crate) fn line(&self) -> &[u8] {
+        &self.line
+    }
+
+    /// Queue any command.
+    ///
+    /// Requests escape any `;` within their arguments, so commands are only split at the
+    /// separators added here.
+    pub fn push<R>(&mut self, request: &Request<R>) -> Pending<R> {
+        if self.count > 0 {
+            self.line.push(b';');
+        }
+        self.line.extend_from_slice(request.command());
+
+        self.count += 1;
+        Pending {
+            index: self.count - 1,
+            parse: request.parser(),
+        }
+    }
+
+    /// Queue [`Connection::acl_add`](crate::Connection::acl_add).
+    pub fn acl_add<E: ToString>(&mut self, id: AclId, value: E) -> Result<Pending<()>, Error> {
+        Ok(self.push(&protocol::acl_add(id, value)?))
+    }
+
+    /// Queue [`Connection::acl_add_version`](crate::Connection::acl_add_version).
+    pub fn acl_add_version<E: ToString>(
+        &mut self,
+        id: AclId,
+        version: u32,
+        value: E,
+    ) -> Result<Pending<()>, Error> {
+        Ok(self.push(&protocol::acl_add_version(id, version, value)?))
+    }
+
+    /// Queue [`Connection::acl_clear`](crate::Connection::acl_clear).
+    pub fn acl_clear(&mut self, id: AclId) -> Result<Pending<()>, Error> {
+        Ok(self.push(&protocol::acl_clear(id)?))
+    }
+
+    /// Queue [`Connection::acl_data`](crate::Connection::acl_data).
+    pub fn acl_data<E: FromStr>(&mut self, id: AclId) -> Result<Pending<Vec<AclEntry<E>>>, Error> {
+        Ok(self.push(&protocol::acl_data(id)?))
+    }
+
+    /// Queue [`Connection::acl_del`](crate::Connection::acl_del).
+    pub fn acl_del(&mut self, id: AclId, entry: EntryRef) -> Result<Pending<()>, Error> {
+        Ok(self.push(&protocol::acl_del(id, entry)?))
+    }
+
+    /// Queue [`Connection::acl_get`](crate::Connection::acl_get).
+    pub fn acl_get<E: ToString>(
+        &mut self,
+        id: AclId,
+        value: E,
+    ) -> Result<Pending<AclMatch>, Error> {
+        Ok(self.push(&protocol::acl_get(id, value)?))
+    }
+
+    /// Queue [`Connection::acl_list`](crate::Connection::acl_list).
+    pub fn acl_list(&mut self) -> Pending<Vec<Acl>> {
+        self.push(&protocol::acl_list())
+    }
+
+    /// Queue [`Connection::map_add`](crate::Connection::map_add).
+    pub fn map_add<K: ToString, V: ToString>(
+        &mut self,
+        id: MapId,
+        key: K,
+        value: V,
+    ) -> Result<Pending<()>, Error> {
+        Ok(self.push(&protocol::map_add(id, key, value)?))
+    }
+
+    /// Queue [`Connection::map_add_version`](crate::Connection::map_add_version).
+    pub fn map_add_version<K: ToString, V: ToString>(
+        &mut self,
+        id: MapId,
+        version: u32,
+        key: K,
+        value: V,
+    ) -> Result<Pending<()>, Error> {
+        Ok(self.push(&protocol::map_add_version(id, version, key, value)?))
+    }
+
+    /// Queue [`Connection::map_clear`](crate::Connection::map_clear).
+    pub fn map_clear(&mut self, id: MapId) -> Result<Pending<()>, Error> {
+        Ok(self.push(&protocol::map_clear(id)?))
+    }
+
+    /// Queue [`Connection::map_del`](crate::Connection::map_del).
+    pub fn map_del(&mut self, id: MapId, entry: EntryRef) -> Result<Pending<()>, Error> {
+        Ok(self.push(&protocol::map_del(id, entry)?))
+    }
+
+    /// Queue [`Connection::map_entries`](crate::Connection::map_entries).
+    pub fn map_entries<K: FromStr, V: FromStr>(
+        &mut self,
+        id: MapId,
+    ) -> Result<Pending<Vec<MapEntry<K, V>>>, Error> {
+        Ok(self.push(&protocol::map_entries(id)?))
+    }
+
+    /// Queue [`Connection::map_get`](crate::Connection::map_get).
+    pub fn map_get<K: ToString>(&mut self, id: MapId, key: K) -> Result<Pending<MapMatch>, Error> {
+        Ok(self.push(&protocol::map_get(id, key)?))
+    }
+
+    /// Queue [`Connection::map_list`](crate::Connection::map_list).
+    pub fn map_list(&mut self) -> Pending<Vec<Map>> {
+        self.push(&protocol::map_list())
+    }
+
+    /// Queue [`Connection::map_set`](crate::Connection::map_set).
+    pub fn map_set<V: ToString>(
+        &mut self,
+        id: MapId,
+        entry: EntryRef,
+        value: V,
+    ) -> Result<Pending<()>, Error> {
+        Ok(self.push(&protocol::map_set(id, entry, value)?))
+    }
+}
+
+/// The responses to the commands of a [`Batch`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BatchResponses {
+    responses: Vec<Vec<u8>>,
+}
+
+impl BatchResponses {
+    /// Split the output of a batch at the empty line which ends each response.
+    pub(crate) fn split(output: &[u8]) -> Result<Self, Error> {
+        let mut responses = Vec::new();
+        let mut current = Vec::new();
+
+        for line in output.split_inclusive(|byte| *byte == b'\n') {
+            current.extend_from_slice(line);
+            if line == b"\n" {
+                responses.push(std::mem::take(&mut current));
+            }
+        }
+        if !current.is_empty() {
+            responses.push(current);
+        }
+
+        Ok(Self { responses })
+    }
+
+    /// The number of responses received.
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    /// Whether no responses were received.
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    /// Parse the response to a queued command.
+    ///
+    /// Returns `Error::ParseFailure` if HAProxy did not respond to the command, for example
+    /// because `pending` belongs to a different batch.
+    pub fn get<R>(&self, pending: &Pending<R>) -> Result<R, Error> {
+        let response = self
+            .responses
+            .get(pending.index)
+            .ok_or(Error::ParseFailure)?;
+        let mut reader = BufReader::new(Cursor::new(response.clone()));
+        let result = (pending.parse)(&mut reader);
+
+        // Fail rather than silently ignoring output which belongs to the command.
+        if result.is_ok() && !reader.fill_buf()?.iter().all(|byte| *byte == b'\n') {
+            return Err(Error::ParseFailure);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_line() {
+        let mut batch = Batch::new();
+        batch.acl_add(AclId::Id(0), "127.0.0.1").unwrap();
+        batch
+            .map_add(MapId::Id(1), "key", "value;with;semicolons")
+            .unwrap();
+        batch.acl_list();
+
+        assert_eq!(batch.len(), 3);
+        assert_eq!(
+            batch.line(),
+            &br"add acl #0 127.0.0.1;add map #1 key value\;with\;semicolons;show acl"[..]
+        );
+        assert_eq!(batch.encoded_len(), batch.line().len());
+    }
+
+    #[test]
+    fn batch_line_escapes_backslashes() {
+        let mut batch = Batch::new();
+        batch
+            .map_set(MapId::Id(1), EntryRef::Key("key"), "C:\\")
+            .unwrap();
+        batch.map_del(MapId::Id(1), EntryRef::Key("other")).unwrap();
+
+        assert_eq!(batch.line(), &br"set map #1 key C:\\;del map #1 other"[..]);
+    }
+
+    #[test]
+    fn batch_responses() {
+        let mut batch = Batch::new();
+        let added = batch.acl_add(AclId::Id(0), "127.0.0.1").unwrap();
+        let entries = batch.acl_data::<String>(AclId::Id(0)).unwrap();
+        let missing = batch
+            .map_del(MapId::Id(1), EntryRef::Key("missing"))
+            .unwrap();
+        let unanswered = batch.acl_clear(AclId::Id(0)).unwrap();
+
+        let responses =
+            BatchResponses::split(b"\n0x1 127.0.0.1\n0x2 127.0.0.2\n\nKey not found.\n\n").unwrap();
+
+        assert_eq!(responses.len(), 3);
+        responses.get(&added).unwrap();
+        assert_eq!(responses.get(&entries).unwrap().len(), 2);
+        assert!(matches!(responses.get(&missing), Err(Error::NotFound)));
+        assert!(matches!(
+            responses.get(&unanswered),
+            Err(Error::ParseFailure)
+        ));
+    }
+}
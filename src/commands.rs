@@ -1,15 +1,281 @@
 //! Format commands.
 
-use crate::models::AclId;
-use crate::requests::{BackendId, ErrorFlag};
+use crate::models::{AclId, MapId, StickTableKey};
+use crate::requests::{
+    AgentState, BackendId, EntryRef, ErrorFlag, HealthState, RateLimit, ServerId, ServerSpec,
+    ServerState, StatFilter, TableFilter, Weight,
+};
+use std::fmt::{self, Display, Write as _};
 use std::io::{Result, Write};
+use std::net::IpAddr;
+
+/// Formats a value as a single argument, so that HAProxy reads it as written.
+///
+/// HAProxy splits arguments at spaces and tabs, ends the command at `;` and treats `\` as an
+/// escape character, so each of them is preceded by a `\`.
+pub(crate) struct Arg<T>(pub(crate) T);
+
+impl<T: Display> Display for Arg<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in self.0.to_string().chars() {
+            if matches!(c, ' ' | '\t' | ';' | '\\') {
+                f.write_char('\\')?;
+            }
+            f.write_char(c)?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the `<backend>/<server>` argument of server commands.
+///
+/// Unlike other commands, server commands interpret a bare number as a name, so numeric IDs are
+/// prefixed with `#`. `BackendId::All` is not meaningful here and must be rejected by callers.
+struct ServerPath<'a>(&'a BackendId<'a>, &'a ServerId<'a>);
+
+impl Display for ServerPath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            BackendId::All => f.write_str("-1")?,
+            BackendId::Id(id) => f.write_fmt(format_args!("#{}", id))?,
+            BackendId::Name(name) => Arg(name).fmt(f)?,
+        }
+        f.write_fmt(format_args!("/{}", Arg(self.1)))
+    }
+}
 
 pub fn end<W: Write>(w: &mut W) -> Result<()> {
     w.write_all(b"\n")
 }
 
-pub fn add_acl<W: Write>(w: &mut W, id: AclId, entry: &str) -> Result<()> {
-    w.write_fmt(format_args!("add acl {} {}", id, entry))
+pub fn add_acl<W: Write>(w: &mut W, version: Option<u32>, id: AclId, entry: &str) -> Result<()> {
+    match version {
+        Some(version) => w.write_fmt(format_args!(
+            "add acl @{} {} {}",
+            version,
+            Arg(id),
+            Arg(entry)
+        )),
+        None => w.write_fmt(format_args!("add acl {} {}", Arg(id), Arg(entry))),
+    }
+}
+
+pub fn add_server<W: Write>(
+    w: &mut W,
+    backend: BackendId,
+    server: &str,
+    spec: &ServerSpec,
+) -> Result<()> {
+    w.write_fmt(format_args!(
+        "add server {} {}",
+        ServerPath(&backend, &ServerId::Name(server)),
+        spec
+    ))
+}
+
+pub fn clear_acl<W: Write>(w: &mut W, version: Option<u32>, id: AclId) -> Result<()> {
+    match version {
+        Some(version) => w.write_fmt(format_args!("clear acl @{} {}", version, Arg(id))),
+        None => w.write_fmt(format_args!("clear acl {}", Arg(id))),
+    }
+}
+
+pub fn clear_table<W: Write>(w: &mut W, table: &str, filter: Option<&TableFilter>) -> Result<()> {
+    match filter {
+        Some(filter) => w.write_fmt(format_args!("clear table {} {}", Arg(table), filter)),
+        None => w.write_fmt(format_args!("clear table {}", Arg(table))),
+    }
+}
+
+pub fn commit_acl<W: Write>(w: &mut W, version: u32, id: AclId) -> Result<()> {
+    w.write_fmt(format_args!("commit acl @{} {}", version, Arg(id)))
+}
+
+pub fn del_acl<W: Write>(w: &mut W, id: AclId, entry: EntryRef) -> Result<()> {
+    w.write_fmt(format_args!("del acl {} {}", Arg(id), Arg(entry)))
+}
+
+pub fn del_server<W: Write>(w: &mut W, backend: BackendId, server: ServerId) -> Result<()> {
+    w.write_fmt(format_args!("del server {}", ServerPath(&backend, &server)))
+}
+
+pub fn disable_frontend<W: Write>(w: &mut W, frontend: &str) -> Result<()> {
+    w.write_fmt(format_args!("disable frontend {}", Arg(frontend)))
+}
+
+pub fn disable_health<W: Write>(w: &mut W, backend: BackendId, server: ServerId) -> Result<()> {
+    w.write_fmt(format_args!(
+        "disable health {}",
+        ServerPath(&backend, &server)
+    ))
+}
+
+pub fn disable_server<W: Write>(w: &mut W, backend: BackendId, server: ServerId) -> Result<()> {
+    w.write_fmt(format_args!(
+        "disable server {}",
+        ServerPath(&backend, &server)
+    ))
+}
+
+pub fn enable_frontend<W: Write>(w: &mut W, frontend: &str) -> Result<()> {
+    w.write_fmt(format_args!("enable frontend {}", Arg(frontend)))
+}
+
+pub fn enable_health<W: Write>(w: &mut W, backend: BackendId, server: ServerId) -> Result<()> {
+    w.write_fmt(format_args!(
+        "enable health {}",
+        ServerPath(&backend, &server)
+    ))
+}
+
+pub fn enable_server<W: Write>(w: &mut W, backend: BackendId, server: ServerId) -> Result<()> {
+    w.write_fmt(format_args!(
+        "enable server {}",
+        ServerPath(&backend, &server)
+    ))
+}
+
+pub fn get_acl<W: Write>(w: &mut W, id: AclId, value: &str) -> Result<()> {
+    w.write_fmt(format_args!("get acl {} {}", Arg(id), Arg(value)))
+}
+
+pub fn prepare_acl<W: Write>(w: &mut W, id: AclId) -> Result<()> {
+    w.write_fmt(format_args!("prepare acl {}", Arg(id)))
+}
+
+pub fn prompt<W: Write>(w: &mut W) -> Result<()> {
+    w.write_all(b"prompt")
+}
+
+pub fn set_maxconn_frontend<W: Write>(w: &mut W, frontend: &str, maxconn: u32) -> Result<()> {
+    w.write_fmt(format_args!(
+        "set maxconn frontend {} {}",
+        Arg(frontend),
+        maxconn
+    ))
+}
+
+pub fn set_maxconn_global<W: Write>(w: &mut W, maxconn: u32) -> Result<()> {
+    w.write_fmt(format_args!("set maxconn global {}", maxconn))
+}
+
+pub fn set_maxconn_server<W: Write>(
+    w: &mut W,
+    backend: BackendId,
+    server: ServerId,
+    maxconn: u32,
+) -> Result<()> {
+    w.write_fmt(format_args!(
+        "set maxconn server {} {}",
+        ServerPath(&backend, &server),
+        maxconn
+    ))
+}
+
+pub fn set_rate_limit<W: Write>(w: &mut W, limit: RateLimit, value: u32) -> Result<()> {
+    w.write_fmt(format_args!("set rate-limit {} global {}", limit, value))
+}
+
+pub fn set_server_addr<W: Write>(
+    w: &mut W,
+    backend: BackendId,
+    server: ServerId,
+    addr: IpAddr,
+    port: Option<u16>,
+) -> Result<()> {
+    w.write_fmt(format_args!(
+        "set server {} addr {}",
+        ServerPath(&backend, &server),
+        addr
+    ))?;
+    match port {
+        Some(port) => w.write_fmt(format_args!(" port {}", port)),
+        None => Ok(()),
+    }
+}
+
+pub fn set_server_agent<W: Write>(
+    w: &mut W,
+    backend: BackendId,
+    server: ServerId,
+    state: AgentState,
+) -> Result<()> {
+    w.write_fmt(format_args!(
+        "set server {} agent {}",
+        ServerPath(&backend, &server),
+        state
+    ))
+}
+
+pub fn set_server_health<W: Write>(
+    w: &mut W,
+    backend: BackendId,
+    server: ServerId,
+    state: HealthState,
+) -> Result<()> {
+    w.write_fmt(format_args!(
+        "set server {} health {}",
+        ServerPath(&backend, &server),
+        state
+    ))
+}
+
+pub fn set_server_state<W: Write>(
+    w: &mut W,
+    backend: BackendId,
+    server: ServerId,
+    state: ServerState,
+) -> Result<()> {
+    w.write_fmt(format_args!(
+        "set server {} state {}",
+        ServerPath(&backend, &server),
+        state
+    ))
+}
+
+pub fn set_table<W: Write>(
+    w: &mut W,
+    table: &str,
+    key: &StickTableKey,
+    data: &[(&str, u64)],
+) -> Result<()> {
+    w.write_fmt(format_args!("set table {} key {}", Arg(table), Arg(key)))?;
+    for (data_type, value) in data {
+        w.write_fmt(format_args!(" data.{} {}", Arg(data_type), value))?;
+    }
+    Ok(())
+}
+
+pub fn set_server_weight<W: Write>(
+    w: &mut W,
+    backend: BackendId,
+    server: ServerId,
+    weight: Weight,
+) -> Result<()> {
+    w.write_fmt(format_args!(
+        "set server {} weight {}",
+        ServerPath(&backend, &server),
+        weight
+    ))
+}
+
+pub fn shutdown_frontend<W: Write>(w: &mut W, frontend: &str) -> Result<()> {
+    w.write_fmt(format_args!("shutdown frontend {}", Arg(frontend)))
+}
+
+pub fn shutdown_sessions_server<W: Write>(
+    w: &mut W,
+    backend: BackendId,
+    server: ServerId,
+) -> Result<()> {
+    w.write_fmt(format_args!(
+        "shutdown sessions server {}",
+        ServerPath(&backend, &server)
+    ))
+}
+
+pub fn shutdown_session<W: Write>(w: &mut W, ptr: u64) -> Result<()> {
+    w.write_fmt(format_args!("shutdown session {:#x}", ptr))
 }
 
 pub fn show_acl<W: Write>(w: &mut W) -> Result<()> {
@@ -17,7 +283,71 @@ pub fn show_acl<W: Write>(w: &mut W) -> Result<()> {
 }
 
 pub fn show_acl_entries<W: Write>(w: &mut W, id: AclId) -> Result<()> {
-    w.write_fmt(format_args!("show acl {}", id))
+    w.write_fmt(format_args!("show acl {}", Arg(id)))
+}
+
+pub fn add_map<W: Write>(
+    w: &mut W,
+    version: Option<u32>,
+    id: MapId,
+    key: &str,
+    value: &str,
+) -> Result<()> {
+    match version {
+        Some(version) => w.write_fmt(format_args!(
+            "add map @{} {} {} {}",
+            version,
+            Arg(id),
+            Arg(key),
+            Arg(value)
+        )),
+        None => w.write_fmt(format_args!(
+            "add map {} {} {}",
+            Arg(id),
+            Arg(key),
+            Arg(value)
+        )),
+    }
+}
+
+pub fn clear_map<W: Write>(w: &mut W, version: Option<u32>, id: MapId) -> Result<()> {
+    match version {
+        Some(version) => w.write_fmt(format_args!("clear map @{} {}", version, Arg(id))),
+        None => w.write_fmt(format_args!("clear map {}", Arg(id))),
+    }
+}
+
+pub fn commit_map<W: Write>(w: &mut W, version: u32, id: MapId) -> Result<()> {
+    w.write_fmt(format_args!("commit map @{} {}", version, Arg(id)))
+}
+
+pub fn del_map<W: Write>(w: &mut W, id: MapId, entry: EntryRef) -> Result<()> {
+    w.write_fmt(format_args!("del map {} {}", Arg(id), Arg(entry)))
+}
+
+pub fn get_map<W: Write>(w: &mut W, id: MapId, key: &str) -> Result<()> {
+    w.write_fmt(format_args!("get map {} {}", Arg(id), Arg(key)))
+}
+
+pub fn set_map<W: Write>(w: &mut W, id: MapId, entry: EntryRef, value: &str) -> Result<()> {
+    w.write_fmt(format_args!(
+        "set map {} {} {}",
+        Arg(id),
+        Arg(entry),
+        Arg(value)
+    ))
+}
+
+pub fn prepare_map<W: Write>(w: &mut W, id: MapId) -> Result<()> {
+    w.write_fmt(format_args!("prepare map {}", Arg(id)))
+}
+
+pub fn show_map<W: Write>(w: &mut W) -> Result<()> {
+    w.write_all(b"show map")
+}
+
+pub fn show_map_entries<W: Write>(w: &mut W, id: MapId) -> Result<()> {
+    w.write_fmt(format_args!("show map {}", Arg(id)))
 }
 
 pub fn show_cli_level<W: Write>(w: &mut W) -> Result<()> {
@@ -28,6 +358,82 @@ pub fn show_cli_sockets<W: Write>(w: &mut W) -> Result<()> {
     w.write_all(b"show cli sockets")
 }
 
+pub fn show_info<W: Write>(w: &mut W) -> Result<()> {
+    w.write_all(b"show info")
+}
+
+#[cfg(feature = "json")]
+pub fn show_info_json<W: Write>(w: &mut W) -> Result<()> {
+    w.write_all(b"show info json")
+}
+
+#[cfg(feature = "json")]
+pub fn show_schema_json<W: Write>(w: &mut W) -> Result<()> {
+    w.write_all(b"show schema json")
+}
+
+pub fn show_info_typed<W: Write>(w: &mut W) -> Result<()> {
+    w.write_all(b"show info typed")
+}
+
+pub fn show_stat<W: Write>(w: &mut W) -> Result<()> {
+    w.write_all(b"show stat")
+}
+
+#[cfg(feature = "json")]
+pub fn show_stat_json<W: Write>(w: &mut W) -> Result<()> {
+    w.write_all(b"show stat json")
+}
+
+pub fn show_stat_typed<W: Write>(w: &mut W) -> Result<()> {
+    w.write_all(b"show stat typed")
+}
+
+pub fn show_backend<W: Write>(w: &mut W) -> Result<()> {
+    w.write_all(b"show backend")
+}
+
+pub fn show_servers_conn<W: Write>(w: &mut W, backend: Option<&str>) -> Result<()> {
+    match backend {
+        Some(backend) => w.write_fmt(format_args!("show servers conn {}", Arg(backend))),
+        None => w.write_all(b"show servers conn"),
+    }
+}
+
+pub fn show_servers_state<W: Write>(w: &mut W, backend: Option<&str>) -> Result<()> {
+    match backend {
+        Some(backend) => w.write_fmt(format_args!("show servers state {}", Arg(backend))),
+        None => w.write_all(b"show servers state"),
+    }
+}
+
+pub fn show_sess<W: Write>(w: &mut W) -> Result<()> {
+    w.write_all(b"show sess")
+}
+
+pub fn show_sess_detail<W: Write>(w: &mut W, ptr: u64) -> Result<()> {
+    w.write_fmt(format_args!("show sess {:#x}", ptr))
+}
+
+pub fn show_table<W: Write>(w: &mut W) -> Result<()> {
+    w.write_all(b"show table")
+}
+
+pub fn show_table_entries<W: Write>(
+    w: &mut W,
+    table: &str,
+    filter: Option<&TableFilter>,
+) -> Result<()> {
+    match filter {
+        Some(filter) => w.write_fmt(format_args!("show table {} {}", Arg(table), filter)),
+        None => w.write_fmt(format_args!("show table {}", Arg(table))),
+    }
+}
+
+pub fn show_stat_filtered<W: Write>(w: &mut W, filter: StatFilter) -> Result<()> {
+    w.write_fmt(format_args!("show stat {}", filter))
+}
+
 pub fn show_errors<W: Write>(w: &mut W) -> Result<()> {
     w.write_all(b"show errors")
 }
@@ -42,5 +448,84 @@ pub fn show_errors_backend<W: Write>(
         ErrorFlag::Request => " request",
         ErrorFlag::Response => " response",
     };
-    w.write_fmt(format_args!("show errors {}{}", id, error_type_str))
+    w.write_fmt(format_args!("show errors {}{}", Arg(id), error_type_str))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_path_display() {
+        assert_eq!(
+            ServerPath(&BackendId::Name("servers"), &ServerId::Name("server1")).to_string(),
+            "servers/server1"
+        );
+        assert_eq!(
+            ServerPath(&BackendId::Id(3), &ServerId::Id(1)).to_string(),
+            "#3/#1"
+        );
+    }
+
+    #[test]
+    fn set_server_state_format() {
+        let mut buf = Vec::new();
+        set_server_state(
+            &mut buf,
+            BackendId::Name("servers"),
+            ServerId::Name("server1"),
+            ServerState::Drain,
+        )
+        .unwrap();
+        assert_eq!(buf, b"set server servers/server1 state drain");
+    }
+
+    #[test]
+    fn add_server_format() {
+        let mut buf = Vec::new();
+        let spec = ServerSpec::new("127.0.0.1:8002".parse().unwrap()).weight(5);
+        add_server(&mut buf, BackendId::Id(2), "dynamic1", &spec).unwrap();
+        assert_eq!(buf, b"add server #2/dynamic1 127.0.0.1:8002 weight 5");
+    }
+
+    #[test]
+    fn set_table_format() {
+        let mut buf = Vec::new();
+        set_table(
+            &mut buf,
+            "http-in",
+            &StickTableKey::Ip("127.0.0.1".parse().unwrap()),
+            &[("gpc0", 1), ("gpt0", 0)],
+        )
+        .unwrap();
+        assert_eq!(
+            buf,
+            b"set table http-in key 127.0.0.1 data.gpc0 1 data.gpt0 0"
+        );
+    }
+
+    #[test]
+    fn set_server_addr_format() {
+        let mut buf = Vec::new();
+        set_server_addr(
+            &mut buf,
+            BackendId::Name("servers"),
+            ServerId::Name("server1"),
+            "::1".parse().unwrap(),
+            Some(8000),
+        )
+        .unwrap();
+        assert_eq!(buf, b"set server servers/server1 addr ::1 port 8000");
+
+        let mut buf = Vec::new();
+        set_server_addr(
+            &mut buf,
+            BackendId::Name("servers"),
+            ServerId::Name("server1"),
+            "127.0.0.1".parse().unwrap(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(buf, b"set server servers/server1 addr 127.0.0.1");
+    }
 }
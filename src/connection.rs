@@ -1,14 +1,23 @@
 use std::io::{self, BufRead, BufReader, Read, Write};
-use std::net;
+use std::net::{self, IpAddr};
 use std::os::unix::net::UnixStream;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
 
+use crate::batch::{Batch, BatchResponses};
 use crate::commands;
 use crate::errors::Error;
+use crate::interactive::InteractiveConnection;
 use crate::models;
-use crate::parsers;
-use crate::requests::{BackendId, ErrorFlag};
+use crate::protocol::{self, Command, Response};
+use crate::requests::{
+    AgentState, BackendId, EntryRef, ErrorFlag, HealthState, RateLimit, ServerId, ServerSpec,
+    ServerState, StatFilter, TableFilter, Weight,
+};
 use crate::responses::{self, Acl};
 
 /// Support connections to HAProxy via Unix sockets and TCP sockets using the same interface.
@@ -25,7 +34,8 @@ pub trait ConnectionBuilder {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UnixSocketBuilder {
     /// The path of the Unix socket.
-    path: PathBuf,
+    pub(crate) path: PathBuf,
+    pub(crate) timeouts: Timeouts,
 }
 
 impl UnixSocketBuilder {
@@ -41,16 +51,65 @@ impl UnixSocketBuilder {
     pub fn new<P: AsRef<Path>>(path: P) -> Self {
         Self {
             path: path.as_ref().to_path_buf(),
+            timeouts: Timeouts::default(),
         }
     }
+
+    /// Fail to connect if HAProxy does not accept the connection within `timeout`.
+    ///
+    /// [`ConnectionBuilder::connect`] then fails with an I/O error of kind
+    /// [`io::ErrorKind::TimedOut`].
+    ///
+    /// Connecting to a Unix socket only blocks when HAProxy's accept queue is full. The connection
+    /// is made on a helper thread, which is left blocked in the background if it times out, until
+    /// HAProxy accepts or refuses the connection. At most 16 such threads are kept across the whole
+    /// process, shared by every builder and socket path. While that many are still blocked,
+    /// connecting with a timeout fails immediately with an I/O error of kind
+    /// [`io::ErrorKind::WouldBlock`] instead of spawning more.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default()
+    ///     .connect_timeout(Duration::from_secs(1))
+    ///     .read_timeout(Duration::from_secs(5))
+    ///     .write_timeout(Duration::from_secs(5))
+    ///     .deadline(Duration::from_secs(10));
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// ```
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.connect = Some(timeout);
+        self
+    }
+
+    /// Fail commands with [`Error::Timeout`] if HAProxy sends nothing for `timeout`.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.read = Some(timeout);
+        self
+    }
+
+    /// Fail commands with [`Error::Timeout`] if a command cannot be written within `timeout`.
+    pub fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.write = Some(timeout);
+        self
+    }
+
+    /// Fail commands with [`Error::Timeout`] if the complete response has not been received
+    /// within `deadline` of sending the command.
+    ///
+    /// In interactive mode, the deadline applies to each command in turn.
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.timeouts.deadline = Some(deadline);
+        self
+    }
 }
 
 /// Use a default location of `/var/run/haproxy.sock` for the Unix socket.
 impl Default for UnixSocketBuilder {
     fn default() -> Self {
-        Self {
-            path: PathBuf::from("/var/run/haproxy.sock"),
-        }
+        Self::new("/var/run/haproxy.sock")
     }
 }
 
@@ -58,17 +117,70 @@ impl ConnectionBuilder for UnixSocketBuilder {
     type Connection = Connection<UnixStream>;
 
     fn connect(&self) -> Result<Self::Connection, io::Error> {
-        let socket = UnixStream::connect(&self.path)?;
+        let socket = match self.timeouts.connect {
+            Some(timeout) => connect_unix_timeout(&self.path, timeout)?,
+            None => UnixStream::connect(&self.path)?,
+        };
+        socket.set_read_timeout(self.timeouts.read)?;
+        socket.set_write_timeout(self.timeouts.write)?;
         let reader = BufReader::new(socket.try_clone()?);
 
-        Ok(Connection { socket, reader })
+        Ok(Connection {
+            socket,
+            reader,
+            deadline: self
+                .timeouts
+                .deadline(UnixStream::set_read_timeout, UnixStream::set_write_timeout),
+            timeouts: self.timeouts,
+        })
     }
 }
 
 impl From<PathBuf> for UnixSocketBuilder {
     fn from(path: PathBuf) -> Self {
-        Self { path }
+        Self {
+            path,
+            timeouts: Timeouts::default(),
+        }
+    }
+}
+
+/// The most helper threads [`connect_unix_timeout`] leaves running at once.
+const MAX_CONNECT_THREADS: usize = 16;
+
+/// The number of helper threads still connecting, including those which already timed out.
+static CONNECT_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Connect to a Unix socket on a helper thread, giving up after `timeout`.
+///
+/// A thread which times out keeps blocking until the socket accepts or refuses the connection, so
+/// no new thread is spawned while `MAX_CONNECT_THREADS` are outstanding.
+fn connect_unix_timeout(path: &Path, timeout: Duration) -> Result<UnixStream, io::Error> {
+    let timed_out = || io::Error::new(io::ErrorKind::TimedOut, "connection timed out");
+
+    if CONNECT_THREADS
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |threads| {
+            (threads < MAX_CONNECT_THREADS).then_some(threads + 1)
+        })
+        .is_err()
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::WouldBlock,
+            "all connect helper threads are still blocked on earlier connections",
+        ));
     }
+
+    let (sender, receiver) = mpsc::channel();
+    let path = path.to_path_buf();
+    thread::spawn(move || {
+        let result = UnixStream::connect(path);
+        CONNECT_THREADS.fetch_sub(1, Ordering::SeqCst);
+        sender.send(result)
+    });
+
+    receiver
+        .recv_timeout(timeout)
+        .unwrap_or_else(|_| Err(timed_out()))
 }
 
 /// Configuration for connecting to HAProxy TCP socket.
@@ -83,24 +195,67 @@ impl From<PathBuf> for UnixSocketBuilder {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct TcpSocketBuilder {
     /// The address of the TCP socket.
-    addr: net::SocketAddr,
+    pub(crate) addr: net::SocketAddr,
+    pub(crate) timeouts: Timeouts,
 }
 
 impl TcpSocketBuilder {
     pub fn new(addr: net::SocketAddr) -> Self {
-        Self { addr }
+        Self {
+            addr,
+            timeouts: Timeouts::default(),
+        }
+    }
+
+    /// Fail to connect if the connection is not established within `timeout`.
+    ///
+    /// [`ConnectionBuilder::connect`] then fails with an I/O error of kind
+    /// [`io::ErrorKind::TimedOut`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use haptik::{ConnectionBuilder, TcpSocketBuilder};
+    ///
+    /// let socket_builder = TcpSocketBuilder::default()
+    ///     .connect_timeout(Duration::from_secs(1))
+    ///     .deadline(Duration::from_secs(10));
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// ```
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.connect = Some(timeout);
+        self
+    }
+
+    /// Fail commands with [`Error::Timeout`] if HAProxy sends nothing for `timeout`.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.read = Some(timeout);
+        self
+    }
+
+    /// Fail commands with [`Error::Timeout`] if a command cannot be written within `timeout`.
+    pub fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.write = Some(timeout);
+        self
+    }
+
+    /// Fail commands with [`Error::Timeout`] if the complete response has not been received
+    /// within `deadline` of sending the command.
+    ///
+    /// In interactive mode, the deadline applies to each command in turn.
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.timeouts.deadline = Some(deadline);
+        self
     }
 }
 
 /// Use a default location of `127.0.0.1:9999` for the TCP connection.
 impl Default for TcpSocketBuilder {
     fn default() -> Self {
-        Self {
-            addr: net::SocketAddr::V4(net::SocketAddrV4::new(
-                net::Ipv4Addr::new(127, 0, 0, 1),
-                9999,
-            )),
-        }
+        Self::new(net::SocketAddr::V4(net::SocketAddrV4::new(
+            net::Ipv4Addr::new(127, 0, 0, 1),
+            9999,
+        )))
     }
 }
 
@@ -108,29 +263,220 @@ impl ConnectionBuilder for TcpSocketBuilder {
     type Connection = Connection<net::TcpStream>;
 
     fn connect(&self) -> Result<Self::Connection, io::Error> {
-        let socket = net::TcpStream::connect(&self.addr)?;
+        let socket = match self.timeouts.connect {
+            Some(timeout) => net::TcpStream::connect_timeout(&self.addr, timeout)?,
+            None => net::TcpStream::connect(self.addr)?,
+        };
+        socket.set_read_timeout(self.timeouts.read)?;
+        socket.set_write_timeout(self.timeouts.write)?;
         let reader = BufReader::new(socket.try_clone()?);
 
-        Ok(Connection { socket, reader })
+        Ok(Connection {
+            socket,
+            reader,
+            deadline: self.timeouts.deadline(
+                net::TcpStream::set_read_timeout,
+                net::TcpStream::set_write_timeout,
+            ),
+            timeouts: self.timeouts,
+        })
+    }
+}
+
+/// Timeouts configured on a connection builder.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct Timeouts {
+    pub(crate) connect: Option<Duration>,
+    pub(crate) read: Option<Duration>,
+    pub(crate) write: Option<Duration>,
+    pub(crate) deadline: Option<Duration>,
+}
+
+type SetTimeout<T> = fn(&T, Option<Duration>) -> io::Result<()>;
+
+impl Timeouts {
+    fn deadline<T>(
+        &self,
+        set_read_timeout: SetTimeout<T>,
+        set_write_timeout: SetTimeout<T>,
+    ) -> Option<Deadline<T>> {
+        self.deadline.map(|duration| Deadline {
+            duration,
+            timeouts: *self,
+            set_read_timeout,
+            set_write_timeout,
+        })
+    }
+
+    /// Convert an error from a socket read or write which was limited by `timeout`.
+    ///
+    /// Sockets report an expired read or write timeout as `WouldBlock` on Unix, so it is only
+    /// reported as [`Error::Timeout`] when a timeout or deadline was set.
+    pub(crate) fn error(&self, err: io::Error, timeout: Option<Duration>) -> Error {
+        if err.kind() == io::ErrorKind::WouldBlock && (timeout.is_some() || self.deadline.is_some())
+        {
+            Error::Timeout
+        } else {
+            Error::from(err)
+        }
+    }
+}
+
+/// Enforces a per-command deadline by shortening socket timeouts to the time remaining.
+///
+/// Once the deadline has passed, reads and writes fail with `WouldBlock`, as if the socket timeout
+/// had expired, so [`Timeouts::error`] reports them as [`Error::Timeout`].
+#[derive(Debug)]
+pub(crate) struct Deadline<T> {
+    duration: Duration,
+    timeouts: Timeouts,
+    set_read_timeout: SetTimeout<T>,
+    set_write_timeout: SetTimeout<T>,
+}
+
+impl<T> Deadline<T> {
+    /// The time by which a command sent now must complete.
+    pub(crate) fn end(&self) -> Instant {
+        Instant::now() + self.duration
+    }
+
+    pub(crate) fn limit_read(&self, socket: &T, end: Instant) -> io::Result<()> {
+        let timeout = remaining(end, self.timeouts.read)?;
+        (self.set_read_timeout)(socket, Some(timeout))
+    }
+
+    pub(crate) fn limit_write(&self, socket: &T, end: Instant) -> io::Result<()> {
+        let timeout = remaining(end, self.timeouts.write)?;
+        (self.set_write_timeout)(socket, Some(timeout))
     }
 }
 
+/// The time left before `end`, capped at `timeout`.
+fn remaining(end: Instant, timeout: Option<Duration>) -> io::Result<Duration> {
+    let remaining = end.saturating_duration_since(Instant::now());
+    if remaining.is_zero() {
+        return Err(io::ErrorKind::WouldBlock.into());
+    }
+
+    Ok(timeout.map_or(remaining, |timeout| timeout.min(remaining)))
+}
+
 /// A connection to HAProxy via any of the supported transports.
 ///
 /// By convention, connections are closed after each command. Therefore, many of the methods on
 /// `Connection` take `self` to force destruction of the `Connection` instance after use. Use a
-/// `ConnectionBuilder` to create connections for each use.
+/// `ConnectionBuilder` to create connections for each use, or [`Connection::interactive`] to issue
+/// many commands over one connection.
 #[derive(Debug)]
 pub struct Connection<T> {
     socket: T,
     reader: BufReader<T>,
+    deadline: Option<Deadline<T>>,
+    timeouts: Timeouts,
+}
+
+impl<T> Connection<T> {
+    pub(crate) fn new(socket: T, reader: BufReader<T>) -> Self {
+        Self {
+            socket,
+            reader,
+            deadline: None,
+            timeouts: Timeouts::default(),
+        }
+    }
+
+    /// Report errors from a socket on which `timeouts` were set.
+    ///
+    /// Only used to interpret errors; the timeouts must already be set on the socket.
+    pub(crate) fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
 }
 
 impl<T: Read + Write> Connection<T> {
-    /// Add an entry to an HAProxy ACL.
+    /// Send a command and parse the response.
+    ///
+    /// Every other method is built on this, but it can also issue commands from
+    /// [`protocol`] directly.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::protocol;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// let acls = connection.execute(&protocol::acl_list()).expect("Failed to list ACLs");
+    /// ```
+    pub fn execute<C: Command>(
+        mut self,
+        command: &C,
+    ) -> Result<<C::Response as Response>::Output, Error> {
+        let end = self.start_deadline();
+        self.send(&command.to_bytes(), end)?;
+
+        let mut response = command.response();
+        self.receive(end, |data| {
+            response.feed(data);
+        })?;
+        response.finish()
+    }
+
+    /// Keep the connection open across commands using HAProxy's interactive prompt mode.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// let mut connection = connection.interactive().expect("Failed to enter prompt mode");
+    /// let acls = connection.command().acl_list().expect("Failed to list ACLs");
+    /// let maps = connection.command().map_list().expect("Failed to list maps");
+    /// ```
+    pub fn interactive(self) -> Result<InteractiveConnection<T>, Error> {
+        InteractiveConnection::enter(self.socket, self.reader, self.deadline, self.timeouts)
+    }
+
+    /// Send every command queued in a [`Batch`] in a single request.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::batch::Batch;
+    /// use haptik::models::MapId;
     ///
-    /// HAProxy's `add acl` command does not support entries with spaces, so this command truncates
-    /// the value at the first space.
+    /// let mut batch = Batch::new();
+    /// let added = batch
+    ///     .map_add(MapId::Id(0), "example.com", "server1")
+    ///     .expect("Invalid entry");
+    /// let entries = batch
+    ///     .map_entries::<String, String>(MapId::Id(0))
+    ///     .expect("Invalid map");
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// let responses = connection.batch(&batch).expect("Failed to send batch");
+    /// responses.get(&added).expect("Failed to add entry");
+    /// let entries = responses.get(&entries).expect("Failed to list entries");
+    /// ```
+    pub fn batch(mut self, batch: &Batch) -> Result<BatchResponses, Error> {
+        if batch.is_empty() {
+            return Ok(BatchResponses::default());
+        }
+
+        let mut request = batch.line().to_vec();
+        commands::end(&mut request)?;
+        let end = self.start_deadline();
+        self.send(&request, end)?;
+
+        let mut output = Vec::new();
+        self.receive(end, |data| output.extend_from_slice(data))?;
+        BatchResponses::split(&output)
+    }
+
+    /// Add an entry to an HAProxy ACL.
     ///
     /// # Examples
     /// ```no_run
@@ -142,14 +488,63 @@ impl<T: Read + Write> Connection<T> {
     /// let connection = socket_builder.connect().expect("Failed to connect");
     /// connection.acl_add(AclId::Id(0), Ipv4Addr::new(127, 0, 0, 1));
     /// ```
-    pub fn acl_add<E: ToString>(mut self, id: models::AclId, value: E) -> Result<(), Error> {
-        let string = value.to_string();
-        let parts: Vec<&str> = string.splitn(2, ' ').collect();
+    pub fn acl_add<E: ToString>(self, id: models::AclId, value: E) -> Result<(), Error> {
+        self.execute(&protocol::acl_add(id, value)?)
+    }
+
+    /// Add an entry to a prepared version of an HAProxy ACL.
+    ///
+    /// The entry is not visible until the version is committed with [`Connection::acl_commit`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::net::Ipv4Addr;
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::models::AclId;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .acl_add_version(AclId::Id(0), 1, Ipv4Addr::new(127, 0, 0, 1))
+    ///     .expect("Failed to add entry");
+    /// ```
+    pub fn acl_add_version<E: ToString>(
+        self,
+        id: models::AclId,
+        version: u32,
+        value: E,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::acl_add_version(id, version, value)?)
+    }
+
+    /// Remove all entries from an HAProxy ACL.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::models::AclId;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection.acl_clear(AclId::Id(0)).expect("Failed to clear ACL");
+    /// ```
+    pub fn acl_clear(self, id: models::AclId) -> Result<(), Error> {
+        self.execute(&protocol::acl_clear(id)?)
+    }
 
-        commands::add_acl(&mut self.socket, id, parts[0])?;
-        commands::end(&mut self.socket)?;
+    /// Remove all entries from a prepared version of an HAProxy ACL.
+    ///
+    /// This is used to abandon a version created by [`Connection::acl_prepare`].
+    pub fn acl_clear_version(self, id: models::AclId, version: u32) -> Result<(), Error> {
+        self.execute(&protocol::acl_clear_version(id, version)?)
+    }
 
-        parsers::parse_acl_add(&mut self.reader)
+    /// Atomically replace the contents of an HAProxy ACL with a prepared version.
+    ///
+    /// Older versions of the ACL are discarded. See [`crate::transaction::AclTransaction`] for a
+    /// higher-level interface.
+    pub fn acl_commit(self, id: models::AclId, version: u32) -> Result<(), Error> {
+        self.execute(&protocol::acl_commit(id, version)?)
     }
 
     /// Query HAProxy for the contents of an ACL.
@@ -174,13 +569,65 @@ impl<T: Read + Write> Connection<T> {
     /// }
     /// ```
     pub fn acl_data<E: FromStr>(
-        mut self,
+        self,
         id: models::AclId,
     ) -> Result<Vec<models::AclEntry<E>>, Error> {
-        commands::show_acl_entries(&mut self.socket, id)?;
-        commands::end(&mut self.socket)?;
+        self.execute(&protocol::acl_data(id)?)
+    }
+
+    /// Remove entries from an HAProxy ACL, either all entries with a given value or a single
+    /// entry by its element pointer.
+    ///
+    /// Element pointers are reported in the `id` field of the entries returned by
+    /// [`Connection::acl_data`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::net::IpAddr;
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::models::AclId;
+    /// use haptik::requests::EntryRef;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let acl_data = socket_builder
+    ///     .connect()
+    ///     .expect("Failed to connect")
+    ///     .acl_data::<IpAddr>(AclId::Id(0))
+    ///     .expect("Failed to query ACL");
+    /// for acl_entry in acl_data {
+    ///     socket_builder
+    ///         .connect()
+    ///         .expect("Failed to connect")
+    ///         .acl_del(AclId::Id(0), EntryRef::Pointer(acl_entry.id))
+    ///         .expect("Failed to delete entry");
+    /// }
+    /// ```
+    pub fn acl_del(self, id: models::AclId, entry: EntryRef) -> Result<(), Error> {
+        self.execute(&protocol::acl_del(id, entry)?)
+    }
 
-        parsers::parse_acl_entries(&mut self.reader)
+    /// Match a value against an HAProxy ACL, reporting whether it matched and which pattern it
+    /// matched.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::net::Ipv4Addr;
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::models::AclId;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// let acl_match = connection
+    ///     .acl_get(AclId::Id(0), Ipv4Addr::new(127, 0, 0, 1))
+    ///     .expect("Failed to query ACL");
+    /// println!("matched={}, pattern={:?}", acl_match.matched, acl_match.pattern);
+    /// ```
+    pub fn acl_get<E: ToString>(
+        self,
+        id: models::AclId,
+        value: E,
+    ) -> Result<responses::AclMatch, Error> {
+        self.execute(&protocol::acl_get(id, value)?)
     }
 
     /// Query HAProxy for available ACLs.
@@ -199,112 +646,1196 @@ impl<T: Read + Write> Connection<T> {
     ///     println!("ACL: id={}, description={}", acl.id, acl.description);
     /// }
     /// ```
-    pub fn acl_list(mut self) -> Result<Vec<Acl>, Error> {
-        commands::show_acl(&mut self.socket)?;
-        commands::end(&mut self.socket)?;
+    pub fn acl_list(self) -> Result<Vec<Acl>, Error> {
+        self.execute(&protocol::acl_list())
+    }
 
-        parsers::parse_acl_list(&mut self.reader)
+    /// Create a new, empty version of an HAProxy ACL, returning the version number.
+    ///
+    /// Entries can be added to the version with [`Connection::acl_add_version`] without affecting
+    /// the current contents of the ACL until the version is committed with
+    /// [`Connection::acl_commit`]. Requires HAProxy 2.4 or later.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::models::AclId;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// let version = connection.acl_prepare(AclId::Id(0)).expect("Failed to prepare ACL");
+    /// ```
+    pub fn acl_prepare(self, id: models::AclId) -> Result<u32, Error> {
+        self.execute(&protocol::acl_prepare(id)?)
     }
 
-    /// Query HAProxy to determine the current level.
+    /// Find the current numeric ID of the ACL loaded from `path`.
+    ///
+    /// Returns `Error::UnknownId` if no ACL was loaded from `path`.
     ///
     /// # Examples
     /// ```no_run
     /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
-    /// use haptik::responses::Level;
     ///
     /// let socket_builder = UnixSocketBuilder::default();
     /// let connection = socket_builder.connect().expect("Failed to connect");
-    /// assert_eq!(connection.level().expect("Failed to query level"), Level::Admin);
+    /// let acl_id = connection
+    ///     .acl_resolve("/etc/haproxy/blocklist.acl")
+    ///     .expect("Failed to find ACL");
     /// ```
-    pub fn level(mut self) -> Result<responses::Level, Error> {
-        commands::show_cli_level(&mut self.socket)?;
-        commands::end(&mut self.socket)?;
+    pub fn acl_resolve<P: AsRef<Path>>(self, path: P) -> Result<models::AclId, Error> {
+        let path = path.as_ref();
+        self.acl_list()?
+            .into_iter()
+            .find(|acl| acl.reference.as_deref().map(Path::new) == Some(path))
+            .map(|acl| models::AclId::Id(acl.id))
+            .ok_or(Error::UnknownId)
+    }
 
-        let mut buf = String::new();
-        self.reader.read_line(&mut buf)?;
-        buf.pop(); // Remove trailing '\n'
+    /// Add an entry to an HAProxy map.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::models::MapId;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection.map_add(MapId::Id(2), "example.com", "server1").expect("Failed to add entry");
+    /// ```
+    pub fn map_add<K: ToString, V: ToString>(
+        self,
+        id: models::MapId,
+        key: K,
+        value: V,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::map_add(id, key, value)?)
+    }
 
-        responses::Level::from_str(buf.as_str())
+    /// Add an entry to a prepared version of an HAProxy map.
+    ///
+    /// The entry is not visible until the version is committed with [`Connection::map_commit`].
+    pub fn map_add_version<K: ToString, V: ToString>(
+        self,
+        id: models::MapId,
+        version: u32,
+        key: K,
+        value: V,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::map_add_version(id, version, key, value)?)
     }
 
-    /// Query HAProxy for the list of configured CLI sockets.
+    /// Remove all entries from an HAProxy map.
     ///
     /// # Examples
     /// ```no_run
     /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
-    /// use haptik::responses::Level;
+    /// use haptik::models::MapId;
     ///
     /// let socket_builder = UnixSocketBuilder::default();
     /// let connection = socket_builder.connect().expect("Failed to connect");
-    /// println!("{:?}", connection.cli_sockets().expect("Failed to query CLI sockets"));
+    /// connection.map_clear(MapId::Id(2)).expect("Failed to clear map");
     /// ```
-    pub fn cli_sockets(mut self) -> Result<Vec<responses::CliSocket>, Error> {
-        commands::show_cli_sockets(&mut self.socket)?;
-        commands::end(&mut self.socket)?;
+    pub fn map_clear(self, id: models::MapId) -> Result<(), Error> {
+        self.execute(&protocol::map_clear(id)?)
+    }
+
+    /// Remove all entries from a prepared version of an HAProxy map.
+    ///
+    /// This is used to abandon a version created by [`Connection::map_prepare`].
+    pub fn map_clear_version(self, id: models::MapId, version: u32) -> Result<(), Error> {
+        self.execute(&protocol::map_clear_version(id, version)?)
+    }
 
-        parsers::parse_cli_sockets(&mut self.reader)
+    /// Atomically replace the contents of an HAProxy map with a prepared version.
+    ///
+    /// Older versions of the map are discarded. See [`crate::transaction::MapTransaction`] for a
+    /// higher-level interface.
+    pub fn map_commit(self, id: models::MapId, version: u32) -> Result<(), Error> {
+        self.execute(&protocol::map_commit(id, version)?)
     }
 
-    /// Query HAProxy for the error count of all backends and all error types.
+    /// Query HAProxy for the contents of a map.
     ///
-    /// This command is identical to `errors_backend(BackendId::All, ErrorFlag::All)`.
+    /// As with [`Connection::acl_data`], provide the types of the keys and values as type
+    /// parameters. Both must implement `FromStr`; use `String` if the type is unknown.
     ///
     /// # Examples
     /// ```no_run
     /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
-    /// use haptik::responses::Level;
+    /// use haptik::models::MapId;
     ///
     /// let socket_builder = UnixSocketBuilder::default();
     /// let connection = socket_builder.connect().expect("Failed to connect");
-    /// assert_eq!(connection.errors().expect("Failed to query error count"), 0);
+    /// let entries = connection
+    ///     .map_entries::<String, u32>(MapId::Id(2))
+    ///     .expect("Failed to query map");
+    /// for entry in entries.iter() {
+    ///     println!("Map Entry: id={}, key={}, value={}", entry.id, entry.key, entry.value);
+    /// }
     /// ```
-    pub fn errors(mut self) -> Result<u32, Error> {
-        commands::show_errors(&mut self.socket)?;
-        commands::end(&mut self.socket)?;
+    pub fn map_entries<K: FromStr, V: FromStr>(
+        self,
+        id: models::MapId,
+    ) -> Result<Vec<models::MapEntry<K, V>>, Error> {
+        self.execute(&protocol::map_entries(id)?)
+    }
 
-        parsers::parse_errors(&mut self.reader)
+    /// Remove entries from an HAProxy map, either all entries with a given key or a single entry
+    /// by its element pointer.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::models::MapId;
+    /// use haptik::requests::EntryRef;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .map_del(MapId::Id(2), EntryRef::Key("example.com"))
+    ///     .expect("Failed to delete entry");
+    /// ```
+    pub fn map_del(self, id: models::MapId, entry: EntryRef) -> Result<(), Error> {
+        self.execute(&protocol::map_del(id, entry)?)
     }
 
-    /// Query HAProxy for the error count of a specific backend and a specific error type.
+    /// Look up a key in an HAProxy map, reporting whether and how it matched.
     ///
-    /// Passing `BackendId::All` queries errors for all backends; this is the same as passing `-1`
-    /// as the ID, which HAProxy interprets as all.
+    /// This evaluates the map's matching method against `key`, so the matching entry may have a
+    /// different key; for example, an `ip` map matches addresses against subnets.
     ///
     /// # Examples
     /// ```no_run
     /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
-    /// use haptik::requests::{BackendId, ErrorFlag};
-    /// use haptik::responses::Level;
+    /// use haptik::models::MapId;
     ///
     /// let socket_builder = UnixSocketBuilder::default();
     /// let connection = socket_builder.connect().expect("Failed to connect");
-    /// assert_eq!(
-    ///     connection.errors_backend(BackendId::Id(1), ErrorFlag::All)
-    ///         .expect("Failed to query error count"),
-    ///     0
-    /// );
+    /// let map_match = connection.map_get(MapId::Id(2), "example.com").expect("Failed to query map");
+    /// println!("found={}, value={:?}", map_match.found, map_match.value);
     /// ```
-    pub fn errors_backend(
-        mut self,
-        backend: BackendId,
-        error_type: ErrorFlag,
-    ) -> Result<u32, Error> {
-        commands::show_errors_backend(&mut self.socket, backend, error_type)?;
-        commands::end(&mut self.socket)?;
-
-        parsers::parse_errors(&mut self.reader)
+    pub fn map_get<K: ToString>(
+        self,
+        id: models::MapId,
+        key: K,
+    ) -> Result<responses::MapMatch, Error> {
+        self.execute(&protocol::map_get(id, key)?)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Query HAProxy for available maps.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// for map in connection.map_list().expect("Failed to query maps") {
+    ///     println!("Map: id={}, file={:?}", map.id, map.reference);
+    /// }
+    /// ```
+    pub fn map_list(self) -> Result<Vec<responses::Map>, Error> {
+        self.execute(&protocol::map_list())
+    }
 
-    #[test]
-    fn unix_socket_builder_errors_on_invalid_socket() {
-        let builder = UnixSocketBuilder::new("/tmp/invalid.sock");
-        assert_eq!(
+    /// Change the value of entries in an HAProxy map, either all entries with a given key or a
+    /// single entry by its element pointer.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::models::MapId;
+    /// use haptik::requests::EntryRef;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .map_set(MapId::Id(2), EntryRef::Pointer(0x55fd35e0e190), "server2")
+    ///     .expect("Failed to update entry");
+    /// ```
+    pub fn map_set<V: ToString>(
+        self,
+        id: models::MapId,
+        entry: EntryRef,
+        value: V,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::map_set(id, entry, value)?)
+    }
+
+    /// Create a new, empty version of an HAProxy map, returning the version number.
+    ///
+    /// Entries can be added to the version with [`Connection::map_add_version`] without affecting
+    /// the current contents of the map until the version is committed with
+    /// [`Connection::map_commit`]. Requires HAProxy 2.4 or later.
+    pub fn map_prepare(self, id: models::MapId) -> Result<u32, Error> {
+        self.execute(&protocol::map_prepare(id)?)
+    }
+
+    /// Find the current numeric ID of the map loaded from `path`.
+    ///
+    /// Returns `Error::UnknownId` if no map was loaded from `path`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// let map_id = connection
+    ///     .map_resolve("/etc/haproxy/hosts.map")
+    ///     .expect("Failed to find map");
+    /// ```
+    pub fn map_resolve<P: AsRef<Path>>(self, path: P) -> Result<models::MapId, Error> {
+        let path = path.as_ref();
+        self.map_list()?
+            .into_iter()
+            .find(|map| map.reference.as_deref().map(Path::new) == Some(path))
+            .map(|map| models::MapId::Id(map.id))
+            .ok_or(Error::UnknownId)
+    }
+
+    /// Query HAProxy to determine the current level.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::responses::Level;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// assert_eq!(connection.level().expect("Failed to query level"), Level::Admin);
+    /// ```
+    pub fn level(self) -> Result<responses::Level, Error> {
+        self.execute(&protocol::level())
+    }
+
+    /// Query HAProxy for the names of all backends.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// println!("{:?}", connection.backends().expect("Failed to query backends"));
+    /// ```
+    pub fn backends(self) -> Result<Vec<String>, Error> {
+        self.execute(&protocol::backends())
+    }
+
+    /// Query HAProxy for the list of configured CLI sockets.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::responses::Level;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// println!("{:?}", connection.cli_sockets().expect("Failed to query CLI sockets"));
+    /// ```
+    pub fn cli_sockets(self) -> Result<Vec<responses::CliSocket>, Error> {
+        self.execute(&protocol::cli_sockets())
+    }
+
+    /// Query HAProxy for the error count of all backends and all error types.
+    ///
+    /// This command is identical to `errors_backend(BackendId::All, ErrorFlag::All)`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::responses::Level;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// assert_eq!(connection.errors().expect("Failed to query error count"), 0);
+    /// ```
+    pub fn errors(self) -> Result<u32, Error> {
+        self.execute(&protocol::errors())
+    }
+
+    /// Query HAProxy for the error count of a specific backend and a specific error type.
+    ///
+    /// Passing `BackendId::All` queries errors for all backends; this is the same as passing `-1`
+    /// as the ID, which HAProxy interprets as all.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{BackendId, ErrorFlag};
+    /// use haptik::responses::Level;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// assert_eq!(
+    ///     connection.errors_backend(BackendId::Id(1), ErrorFlag::All)
+    ///         .expect("Failed to query error count"),
+    ///     0
+    /// );
+    /// ```
+    pub fn errors_backend(self, backend: BackendId, error_type: ErrorFlag) -> Result<u32, Error> {
+        self.execute(&protocol::errors_backend(backend, error_type)?)
+    }
+
+    /// Add a server to a backend without reloading HAProxy.
+    ///
+    /// Requires HAProxy 2.4 or later and a backend using a dynamic load balancing algorithm. New
+    /// servers start in maintenance mode; use [`Connection::set_server_state`] to put them into
+    /// service. Returns `Error::InvalidArgument` if the name is empty, if a keyword contains `;` or
+    /// a newline, or if the weight is above [`Weight::MAX`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{BackendId, ServerSpec};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// let spec = ServerSpec::new("10.0.0.5:8080".parse().unwrap())
+    ///     .weight(10)
+    ///     .maxconn(100);
+    /// connection
+    ///     .add_server(BackendId::Name("servers"), "server2", &spec)
+    ///     .expect("Failed to add server");
+    /// ```
+    pub fn add_server(
+        self,
+        backend: BackendId,
+        name: &str,
+        spec: &ServerSpec,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::add_server(backend, name, spec)?)
+    }
+
+    /// Remove a server from a backend without reloading HAProxy.
+    ///
+    /// HAProxy only deletes servers which are in maintenance mode and have no remaining
+    /// connections, returning `Error::InvalidState` otherwise. Set the server to
+    /// [`ServerState::Maint`] and wait for its sessions to finish before deleting it, or use
+    /// [`lifecycle::remove_server`](crate::lifecycle::remove_server) to check both conditions
+    /// first.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{BackendId, ServerId};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .del_server(BackendId::Name("servers"), ServerId::Name("server2"))
+    ///     .expect("Failed to delete server");
+    /// ```
+    pub fn del_server(self, backend: BackendId, server: ServerId) -> Result<(), Error> {
+        self.execute(&protocol::del_server(backend, server)?)
+    }
+
+    /// Stop accepting new connections on all listeners of a frontend, without closing them.
+    ///
+    /// Returns `StateChange::Unchanged` if the frontend was already disabled, and
+    /// `Error::InvalidState` if it was shut down.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection.disable_frontend("http").expect("Failed to disable frontend");
+    /// ```
+    pub fn disable_frontend(self, frontend: &str) -> Result<responses::StateChange, Error> {
+        self.execute(&protocol::disable_frontend(frontend)?)
+    }
+
+    /// Stop running health checks on a server.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{BackendId, ServerId};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .disable_health(BackendId::Name("servers"), ServerId::Name("server1"))
+    ///     .expect("Failed to disable health checks");
+    /// ```
+    pub fn disable_health(self, backend: BackendId, server: ServerId) -> Result<(), Error> {
+        self.execute(&protocol::disable_health(backend, server)?)
+    }
+
+    /// Put a server into maintenance mode.
+    ///
+    /// This is equivalent to `set_server_state(backend, server, ServerState::Maint)`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{BackendId, ServerId};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .disable_server(BackendId::Name("servers"), ServerId::Name("server1"))
+    ///     .expect("Failed to disable server");
+    /// ```
+    pub fn disable_server(self, backend: BackendId, server: ServerId) -> Result<(), Error> {
+        self.execute(&protocol::disable_server(backend, server)?)
+    }
+
+    /// Resume accepting connections on a frontend disabled with
+    /// [`Connection::disable_frontend`].
+    ///
+    /// Returns `StateChange::Unchanged` if the frontend was already enabled, and
+    /// `Error::InvalidState` if it was shut down.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection.enable_frontend("http").expect("Failed to enable frontend");
+    /// ```
+    pub fn enable_frontend(self, frontend: &str) -> Result<responses::StateChange, Error> {
+        self.execute(&protocol::enable_frontend(frontend)?)
+    }
+
+    /// Resume health checks on a server, such as a server added with [`Connection::add_server`]
+    /// whose checks are disabled until enabled explicitly.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{BackendId, ServerId};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .enable_health(BackendId::Name("servers"), ServerId::Name("server1"))
+    ///     .expect("Failed to enable health checks");
+    /// ```
+    pub fn enable_health(self, backend: BackendId, server: ServerId) -> Result<(), Error> {
+        self.execute(&protocol::enable_health(backend, server)?)
+    }
+
+    /// Take a server out of maintenance mode.
+    ///
+    /// Unlike `set_server_state(backend, server, ServerState::Ready)`, this only clears forced
+    /// maintenance; a server in drain mode stays in drain mode.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{BackendId, ServerId};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .enable_server(BackendId::Name("servers"), ServerId::Name("server1"))
+    ///     .expect("Failed to enable server");
+    /// ```
+    pub fn enable_server(self, backend: BackendId, server: ServerId) -> Result<(), Error> {
+        self.execute(&protocol::enable_server(backend, server)?)
+    }
+
+    /// Query HAProxy for information about the running process.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// let info = connection.info().expect("Failed to query process info");
+    /// println!("HAProxy {:?} up for {:?} seconds", info.version, info.uptime_sec);
+    /// ```
+    pub fn info(self) -> Result<responses::Info, Error> {
+        self.execute(&protocol::info())
+    }
+
+    /// Query HAProxy for information about the running process in typed format.
+    ///
+    /// Each field is returned separately with its value converted according to the type reported
+    /// by HAProxy. When HAProxy runs multiple processes, each field is reported once per process.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// for field in connection.info_typed().expect("Failed to query process info") {
+    ///     println!("process {}: {} = {:?}", field.process, field.name, field.value);
+    /// }
+    /// ```
+    pub fn info_typed(self) -> Result<Vec<responses::TypedInfo>, Error> {
+        self.execute(&protocol::info_typed())
+    }
+
+    /// Query HAProxy for information about the running process in JSON format.
+    ///
+    /// In addition to the process information, this returns the tags HAProxy reports for each
+    /// field, keyed by field name. Requires the `json` feature.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// let info = connection.info_json().expect("Failed to query process info");
+    /// println!("{:?}: {:?}", info.value.curr_conns, info.tags.get("CurrConns"));
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn info_json(self) -> Result<responses::Tagged<responses::Info>, Error> {
+        self.execute(&protocol::info_json())
+    }
+
+    /// Query HAProxy for the JSON schema describing its JSON output. Requires the `json` feature.
+    #[cfg(feature = "json")]
+    pub fn schema_json(self) -> Result<serde_json::Value, Error> {
+        self.execute(&protocol::schema_json())
+    }
+
+    /// Query HAProxy for the connection counters of servers.
+    ///
+    /// As with [`Connection::servers_state`], `BackendId::Id` returns `Error::InvalidArgument`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// for row in connection.servers_conn(None).expect("Failed to query connections") {
+    ///     println!("{}/{}: {:?} idle", row.be_name, row.srv_name, row.idle_cur);
+    /// }
+    /// ```
+    pub fn servers_conn(
+        self,
+        backend: Option<BackendId>,
+    ) -> Result<Vec<responses::ServerConnections>, Error> {
+        self.execute(&protocol::servers_conn(backend)?)
+    }
+
+    /// Query HAProxy for the state of servers, in the format used to preserve state across
+    /// reloads.
+    ///
+    /// HAProxy only looks up backends by name here, so `BackendId::Id` returns
+    /// `Error::InvalidArgument`. `None` and `BackendId::All` report servers of every backend.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::BackendId;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// let rows = connection
+    ///     .servers_state(Some(BackendId::Name("servers")))
+    ///     .expect("Failed to query server state");
+    /// for row in rows {
+    ///     println!("{}/{}: {:?}", row.be_name, row.srv_name, row.srv_op_state);
+    /// }
+    /// ```
+    pub fn servers_state(
+        self,
+        backend: Option<BackendId>,
+    ) -> Result<Vec<responses::ServerStateRow>, Error> {
+        self.execute(&protocol::servers_state(backend)?)
+    }
+
+    /// Query HAProxy for a list of all sessions.
+    ///
+    /// The list includes the CLI session used to run this command.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// for session in connection.sessions().expect("Failed to query sessions") {
+    ///     println!("{:#x}: {:?} age {:?}", session.ptr, session.src, session.age);
+    /// }
+    /// ```
+    pub fn sessions(self) -> Result<Vec<responses::Session>, Error> {
+        self.execute(&protocol::sessions())
+    }
+
+    /// Query HAProxy for a detailed description of a session.
+    ///
+    /// The description is meant for humans and changes between HAProxy versions, so it is
+    /// returned as text. Returns `Error::NotFound` if the session has ended.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// println!("{}", connection.session(0x55d1c3e6a5f0).expect("Failed to query session"));
+    /// ```
+    pub fn session(self, ptr: u64) -> Result<String, Error> {
+        self.execute(&protocol::session(ptr))
+    }
+
+    /// Change the maximum number of concurrent connections accepted by a frontend.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .set_maxconn_frontend("http-in", 2000)
+    ///     .expect("Failed to change maxconn");
+    /// ```
+    pub fn set_maxconn_frontend(self, frontend: &str, maxconn: u32) -> Result<(), Error> {
+        self.execute(&protocol::set_maxconn_frontend(frontend, maxconn)?)
+    }
+
+    /// Change the maximum number of concurrent connections accepted by the process.
+    ///
+    /// HAProxy returns `Error::InvalidArgument` if `maxconn` exceeds the limit set at startup.
+    /// Zero resets the limit to that startup value.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection.set_maxconn_global(4000).expect("Failed to change maxconn");
+    /// ```
+    pub fn set_maxconn_global(self, maxconn: u32) -> Result<(), Error> {
+        self.execute(&protocol::set_maxconn_global(maxconn)?)
+    }
+
+    /// Change the maximum number of concurrent connections to a server. Zero removes the limit.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{BackendId, ServerId};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .set_maxconn_server(BackendId::Name("servers"), ServerId::Name("server1"), 64)
+    ///     .expect("Failed to change maxconn");
+    /// ```
+    pub fn set_maxconn_server(
+        self,
+        backend: BackendId,
+        server: ServerId,
+        maxconn: u32,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::set_maxconn_server(backend, server, maxconn)?)
+    }
+
+    /// Change a process-wide rate limit. Zero removes the limit.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::RateLimit;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .set_rate_limit(RateLimit::Connections, 500)
+    ///     .expect("Failed to change rate limit");
+    /// ```
+    pub fn set_rate_limit(self, limit: RateLimit, value: u32) -> Result<(), Error> {
+        self.execute(&protocol::set_rate_limit(limit, value)?)
+    }
+
+    /// Change the address and, optionally, the port of a server.
+    ///
+    /// Returns the changes HAProxy reported; if the server already used the given address and
+    /// port, nothing changes.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::net::Ipv4Addr;
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{BackendId, ServerId};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// let change = connection
+    ///     .set_server_addr(
+    ///         BackendId::Name("servers"),
+    ///         ServerId::Name("server1"),
+    ///         Ipv4Addr::new(10, 0, 0, 2).into(),
+    ///         Some(8080),
+    ///     )
+    ///     .expect("Failed to change address");
+    /// println!("{:?} -> {:?}", change.old_addr, change.new_addr);
+    /// ```
+    pub fn set_server_addr(
+        self,
+        backend: BackendId,
+        server: ServerId,
+        addr: IpAddr,
+        port: Option<u16>,
+    ) -> Result<responses::AddrChange, Error> {
+        self.execute(&protocol::set_server_addr(backend, server, addr, port)?)
+    }
+
+    /// Force the agent check state of a server.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{AgentState, BackendId, ServerId};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .set_server_agent(BackendId::Name("servers"), ServerId::Name("server1"), AgentState::Up)
+    ///     .expect("Failed to set agent state");
+    /// ```
+    pub fn set_server_agent(
+        self,
+        backend: BackendId,
+        server: ServerId,
+        state: AgentState,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::set_server_agent(backend, server, state)?)
+    }
+
+    /// Force the health check state of a server.
+    ///
+    /// HAProxy rejects this command if health checks are not enabled on the server.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{BackendId, HealthState, ServerId};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .set_server_health(
+    ///         BackendId::Name("servers"),
+    ///         ServerId::Name("server1"),
+    ///         HealthState::Down,
+    ///     )
+    ///     .expect("Failed to set health state");
+    /// ```
+    pub fn set_server_health(
+        self,
+        backend: BackendId,
+        server: ServerId,
+        state: HealthState,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::set_server_health(backend, server, state)?)
+    }
+
+    /// Change the administrative state of a server.
+    ///
+    /// Draining a server stops it from receiving new connections while letting existing
+    /// connections finish, which makes it safe to restart once its sessions reach zero.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{BackendId, ServerId, ServerState};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .set_server_state(
+    ///         BackendId::Name("servers"),
+    ///         ServerId::Name("server1"),
+    ///         ServerState::Drain,
+    ///     )
+    ///     .expect("Failed to drain server");
+    /// ```
+    pub fn set_server_state(
+        self,
+        backend: BackendId,
+        server: ServerId,
+        state: ServerState,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::set_server_state(backend, server, state)?)
+    }
+
+    /// Change the weight of a server.
+    ///
+    /// Absolute weights above [`Weight::MAX`] are rejected with `Error::InvalidArgument`. Backends
+    /// using a static load balancing algorithm only accept `Weight::Percent(0)` and
+    /// `Weight::Percent(100)`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{BackendId, ServerId, Weight};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .set_server_weight(
+    ///         BackendId::Name("servers"),
+    ///         ServerId::Name("server1"),
+    ///         Weight::Percent(10),
+    ///     )
+    ///     .expect("Failed to change weight");
+    /// ```
+    pub fn set_server_weight(
+        self,
+        backend: BackendId,
+        server: ServerId,
+        weight: Weight,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::set_server_weight(backend, server, weight)?)
+    }
+
+    /// Stop a frontend and release its listening ports.
+    ///
+    /// This cannot be undone without reloading HAProxy. Returns `StateChange::Unchanged` if the
+    /// frontend was already shut down.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection.shutdown_frontend("http").expect("Failed to shut down frontend");
+    /// ```
+    pub fn shutdown_frontend(self, frontend: &str) -> Result<responses::StateChange, Error> {
+        self.execute(&protocol::shutdown_frontend(frontend)?)
+    }
+
+    /// Remove entries from a stick table.
+    ///
+    /// Without a filter, all entries which are not in use are removed. Returns
+    /// `Error::InvalidArgument` if the filter has no data filters or more than
+    /// [`TableFilter::MAX_DATA_FILTERS`], or an empty string key.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::net::Ipv4Addr;
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::models::StickTableKey;
+    /// use haptik::requests::TableFilter;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// let filter = TableFilter::Key(StickTableKey::Ip(Ipv4Addr::new(192, 0, 2, 1)));
+    /// connection
+    ///     .table_clear("http-in", Some(&filter))
+    ///     .expect("Failed to clear entry");
+    /// ```
+    pub fn table_clear(self, table: &str, filter: Option<&TableFilter>) -> Result<(), Error> {
+        self.execute(&protocol::table_clear(table, filter)?)
+    }
+
+    /// Query HAProxy for a list of all stick tables.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// for table in connection.tables().expect("Failed to query tables") {
+    ///     println!("{}: {}/{} entries", table.name, table.used, table.size);
+    /// }
+    /// ```
+    pub fn tables(self) -> Result<Vec<responses::StickTable>, Error> {
+        self.execute(&protocol::tables())
+    }
+
+    /// Query HAProxy for the entries of a stick table, optionally limited to those matching
+    /// `filter`.
+    ///
+    /// Returns `Error::InvalidArgument` if the filter has no data filters or more than
+    /// [`TableFilter::MAX_DATA_FILTERS`], or an empty string key.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{DataFilter, TableFilter};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// let filter = TableFilter::Data(vec![DataFilter::gt("gpc0", 0)]);
+    /// for entry in connection
+    ///     .table_entries("http-in", Some(&filter))
+    ///     .expect("Failed to query table")
+    /// {
+    ///     println!("{}: {:?}", entry.key, entry.data);
+    /// }
+    /// ```
+    pub fn table_entries(
+        self,
+        table: &str,
+        filter: Option<&TableFilter>,
+    ) -> Result<Vec<responses::StickTableEntry>, Error> {
+        self.execute(&protocol::table_entries(table, filter)?)
+    }
+
+    /// Set data columns of a stick table entry, creating the entry if it does not exist.
+    ///
+    /// `data` pairs the name of each column, such as `gpc0`, with its new value. Every column must
+    /// be stored by the table. Empty string keys are rejected with `Error::InvalidArgument`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::net::Ipv4Addr;
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::models::StickTableKey;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// let key = StickTableKey::Ip(Ipv4Addr::new(192, 0, 2, 1));
+    /// connection
+    ///     .table_set("http-in", &key, &[("gpc0", 0)])
+    ///     .expect("Failed to reset counter");
+    /// ```
+    pub fn table_set(
+        self,
+        table: &str,
+        key: &models::StickTableKey,
+        data: &[(&str, u64)],
+    ) -> Result<(), Error> {
+        self.execute(&protocol::table_set(table, key, data)?)
+    }
+
+    /// Close a session immediately.
+    ///
+    /// Returns `Error::UnknownId` if the session has already ended.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection.shutdown_session(0x55d1c3e6a5f0).expect("Failed to close session");
+    /// ```
+    pub fn shutdown_session(self, ptr: u64) -> Result<(), Error> {
+        self.execute(&protocol::shutdown_session(ptr))
+    }
+
+    /// Close all sessions established with a server.
+    ///
+    /// Combined with [`ServerState::Maint`], this forcibly removes all traffic from a server, for
+    /// example once a drain has taken too long.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{BackendId, ServerId};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// connection
+    ///     .shutdown_sessions_server(BackendId::Name("servers"), ServerId::Name("server1"))
+    ///     .expect("Failed to close sessions");
+    /// ```
+    pub fn shutdown_sessions_server(
+        self,
+        backend: BackendId,
+        server: ServerId,
+    ) -> Result<(), Error> {
+        self.execute(&protocol::shutdown_sessions_server(backend, server)?)
+    }
+
+    /// Query HAProxy for statistics on all frontends, backends, servers and listeners.
+    ///
+    /// Columns are matched by the names in the CSV header, so this works across HAProxy versions
+    /// which add or remove columns. See [`responses::StatRow`] for the available statistics.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// for row in connection.stats().expect("Failed to query stats") {
+    ///     println!("{}/{}: {:?} sessions", row.pxname, row.svname, row.scur);
+    /// }
+    /// ```
+    pub fn stats(self) -> Result<Vec<responses::StatRow>, Error> {
+        self.execute(&protocol::stats())
+    }
+
+    /// Query HAProxy for statistics on all frontends, backends, servers and listeners in typed
+    /// format.
+    ///
+    /// Each field of each object is returned separately with its value converted according to the
+    /// type reported by HAProxy. When HAProxy runs multiple processes, each field is reported once
+    /// per process.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// for field in connection.stats_typed().expect("Failed to query stats") {
+    ///     println!("proxy {}: {} = {:?}", field.proxy_id, field.name, field.value);
+    /// }
+    /// ```
+    pub fn stats_typed(self) -> Result<Vec<responses::TypedStat>, Error> {
+        self.execute(&protocol::stats_typed())
+    }
+
+    /// Query HAProxy for statistics on all frontends, backends, servers and listeners in JSON
+    /// format.
+    ///
+    /// In addition to the statistics, this returns the tags HAProxy reports for each field, which
+    /// describe whether the field is a gauge, counter, etc. Requires the `json` feature.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::responses::FieldNature;
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// for row in connection.stats_json().expect("Failed to query stats") {
+    ///     for (name, tags) in row.tags.iter() {
+    ///         if tags.nature == FieldNature::Counter {
+    ///             println!("{}/{} counter: {}", row.value.pxname, row.value.svname, name);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn stats_json(self) -> Result<Vec<responses::Tagged<responses::StatRow>>, Error> {
+        self.execute(&protocol::stats_json())
+    }
+
+    /// Query HAProxy for statistics on a subset of proxies, object types and servers.
+    ///
+    /// This avoids transferring every row of `show stat` when only a single backend or server is
+    /// of interest.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+    /// use haptik::requests::{BackendId, StatFilter, StatObjects};
+    ///
+    /// let socket_builder = UnixSocketBuilder::default();
+    /// let connection = socket_builder.connect().expect("Failed to connect");
+    /// let filter = StatFilter {
+    ///     proxy: BackendId::Name("servers"),
+    ///     objects: StatObjects::SERVER,
+    ///     server: None,
+    /// };
+    /// for row in connection.stats_filtered(filter).expect("Failed to query stats") {
+    ///     println!("{}: {:?}", row.svname, row.status);
+    /// }
+    /// ```
+    pub fn stats_filtered(self, filter: StatFilter) -> Result<Vec<responses::StatRow>, Error> {
+        self.execute(&protocol::stats_filtered(filter)?)
+    }
+
+    /// The time by which the command being sent must complete, if a deadline is configured.
+    fn start_deadline(&self) -> Option<Instant> {
+        self.deadline.as_ref().map(Deadline::end)
+    }
+
+    fn send(&mut self, request: &[u8], end: Option<Instant>) -> Result<(), Error> {
+        let result = match (&self.deadline, end) {
+            (Some(deadline), Some(end)) => deadline.limit_write(&self.socket, end),
+            _ => Ok(()),
+        };
+
+        result
+            .and_then(|()| self.socket.write_all(request))
+            .map_err(|err| self.timeouts.error(err, self.timeouts.write))
+    }
+
+    /// Pass each chunk of the response to `feed` until HAProxy closes the connection.
+    fn receive<F: FnMut(&[u8])>(&mut self, end: Option<Instant>, mut feed: F) -> Result<(), Error> {
+        let timeouts = self.timeouts;
+        loop {
+            if let (Some(deadline), Some(end)) = (&self.deadline, end) {
+                deadline
+                    .limit_read(self.reader.get_ref(), end)
+                    .map_err(|err| timeouts.error(err, timeouts.read))?;
+            }
+
+            let data = self
+                .reader
+                .fill_buf()
+                .map_err(|err| timeouts.error(err, timeouts.read))?;
+            if data.is_empty() {
+                return Ok(());
+            }
+            feed(data);
+
+            let len = data.len();
+            self.reader.consume(len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockStream;
+
+    #[test]
+    fn arguments_cannot_inject_commands() {
+        let stream = MockStream::new(b"\n");
+        Connection::new(stream.clone(), BufReader::new(stream.clone()))
+            .map_set(
+                models::MapId::Id(1),
+                EntryRef::Key("key"),
+                "v;shutdown frontend http-in",
+            )
+            .unwrap();
+        assert_eq!(
+            stream.output.borrow().as_slice(),
+            &b"set map #1 key v\\;shutdown\\ frontend\\ http-in\n"[..]
+        );
+
+        let stream = MockStream::new(b"\n");
+        assert!(matches!(
+            Connection::new(stream.clone(), BufReader::new(stream.clone())).map_add(
+                models::MapId::Id(1),
+                "key",
+                "v\nshutdown frontend http-in",
+            ),
+            Err(Error::InvalidArgument)
+        ));
+        assert!(stream.output.borrow().is_empty());
+    }
+
+    #[test]
+    fn would_block_is_timeout_only_with_timeouts() {
+        let stream = MockStream::stalling(b"0x1 127.0.0.1\n");
+        let connection = Connection::new(stream.clone(), BufReader::new(stream.clone()));
+        match connection.acl_list() {
+            Err(Error::IoError(err)) => assert_eq!(err.kind(), io::ErrorKind::WouldBlock),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+
+        let stream = MockStream::stalling(b"0x1 127.0.0.1\n");
+        let connection =
+            Connection::new(stream.clone(), BufReader::new(stream)).with_timeouts(Timeouts {
+                read: Some(Duration::from_secs(1)),
+                ..Timeouts::default()
+            });
+        assert!(matches!(connection.acl_list(), Err(Error::Timeout)));
+    }
+
+    #[test]
+    fn unix_socket_builder_errors_on_invalid_socket() {
+        let builder = UnixSocketBuilder::new("/tmp/invalid.sock");
+        assert_eq!(
             builder
                 .connect()
                 .expect_err("Connected to a Unix socket that does not exist")
@@ -312,4 +1843,90 @@ mod tests {
             io::ErrorKind::NotFound
         );
     }
+
+    #[test]
+    fn exhausted_connect_threads_are_reported() {
+        // Pretend every helper thread is still blocked on an earlier connection.
+        CONNECT_THREADS.fetch_add(MAX_CONNECT_THREADS, Ordering::SeqCst);
+        let result = UnixSocketBuilder::new("/nonexistent/haproxy.sock")
+            .connect_timeout(Duration::from_secs(1))
+            .connect();
+        CONNECT_THREADS.fetch_sub(MAX_CONNECT_THREADS, Ordering::SeqCst);
+
+        assert_eq!(
+            result
+                .expect_err("Connected without a helper thread")
+                .kind(),
+            io::ErrorKind::WouldBlock
+        );
+    }
+
+    /// Listen on a new Unix socket, writing `greeting` and then a newline every `interval` to each
+    /// connection.
+    fn trickling_socket(name: &str, greeting: &'static [u8], interval: Duration) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("haptik-{}-{}.sock", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+        let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                thread::spawn(move || {
+                    if stream.write_all(greeting).is_err() {
+                        return;
+                    }
+                    while stream.write_all(b"\n").is_ok() {
+                        thread::sleep(interval);
+                    }
+                });
+            }
+        });
+        path
+    }
+
+    #[test]
+    fn read_timeout_expires() {
+        let path = trickling_socket("read-timeout", b"", Duration::from_secs(60));
+        let builder = UnixSocketBuilder::new(&path).read_timeout(Duration::from_millis(50));
+
+        assert!(matches!(
+            builder.connect().unwrap().acl_list(),
+            Err(Error::Timeout)
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn deadline_expires() {
+        let path = trickling_socket("deadline", b"", Duration::from_millis(10));
+        let builder = UnixSocketBuilder::new(&path)
+            .read_timeout(Duration::from_secs(60))
+            .deadline(Duration::from_millis(100));
+
+        let start = Instant::now();
+        assert!(matches!(
+            builder.connect().unwrap().acl_list(),
+            Err(Error::Timeout)
+        ));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn deadline_expires_in_interactive_mode() {
+        // Answer the `prompt` command, then never finish the next response.
+        let path = trickling_socket("interactive-deadline", b"> ", Duration::from_millis(10));
+        let builder = UnixSocketBuilder::new(&path)
+            .read_timeout(Duration::from_secs(60))
+            .deadline(Duration::from_millis(100));
+        let mut connection = builder.connect().unwrap().interactive().unwrap();
+
+        let start = Instant::now();
+        assert!(matches!(
+            connection.command().acl_list(),
+            Err(Error::Timeout)
+        ));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        std::fs::remove_file(path).unwrap();
+    }
 }
@@ -11,13 +11,32 @@ pub enum Error {
     /// Command did not have enough parameters.
     MissingParameters,
 
+    /// An argument is not valid for the command.
+    InvalidArgument,
+
+    /// HAProxy was not able to find the referenced entry.
+    NotFound,
+
+    /// The object is not in a state which allows the command, such as deleting a server which is
+    /// not in maintenance mode or still has connections.
+    InvalidState,
+
+    /// HAProxy rejected the command with the contained message.
+    CommandFailed(String),
+
+    /// A timeout or deadline configured on the connection builder expired.
+    Timeout,
+
     /// Error encountered while performing IO.
     IoError(std::io::Error),
 }
 
 impl From<std::io::Error> for Error {
     fn from(err: std::io::Error) -> Self {
-        Error::IoError(err)
+        match err.kind() {
+            std::io::ErrorKind::TimedOut => Error::Timeout,
+            _ => Error::IoError(err),
+        }
     }
 }
 
@@ -26,3 +45,10 @@ impl From<std::num::ParseIntError> for Error {
         Error::ParseFailure
     }
 }
+
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for Error {
+    fn from(_err: serde_json::Error) -> Self {
+        Error::ParseFailure
+    }
+}
@@ -0,0 +1,332 @@
+//! Issue many commands over a single connection using HAProxy's interactive prompt mode.
+//!
+//! By default, HAProxy closes the connection after each command, which is why most methods on
+//! [`Connection`] consume it. After the `prompt` command, HAProxy instead keeps the connection
+//! open and writes a `> ` prompt after each response. [`InteractiveConnection`] uses
+//! [`PromptScanner`] to find the end of each response, so every [`Connection`] method can be used
+//! over one socket.
+
+use std::cell::RefCell;
+use std::io::{self, BufRead, BufReader, Cursor, Read, Write};
+use std::time::Instant;
+
+use crate::commands;
+use crate::connection::{Connection, Deadline, Timeouts};
+use crate::errors::Error;
+use crate::protocol::{Progress, PromptScanner};
+
+/// A connection to HAProxy in interactive prompt mode.
+///
+/// Call [`InteractiveConnection::command`] to get a [`Connection`] for the next command. The
+/// returned connection only reads up to the next prompt, so any number of commands may be issued
+/// in turn.
+///
+/// Responses are delimited by a line starting with `> `, so values which contain a newline
+/// followed by `> ` cannot be read reliably in this mode.
+///
+/// After an I/O error, including an expired timeout, the connection can no longer tell where the
+/// next response starts. Every later command then fails with an I/O error, and a new connection
+/// must be opened.
+///
+/// # Examples
+/// ```no_run
+/// use std::net::Ipv4Addr;
+/// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+/// use haptik::models::AclId;
+///
+/// let socket_builder = UnixSocketBuilder::default();
+/// let connection = socket_builder.connect().expect("Failed to connect");
+/// let mut connection = connection.interactive().expect("Failed to enter prompt mode");
+/// for host in 1..=100 {
+///     connection
+///         .command()
+///         .acl_add(AclId::Id(0), Ipv4Addr::new(10, 0, 0, host))
+///         .expect("Failed to add entry");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct InteractiveConnection<T> {
+    state: RefCell<PromptState<T>>,
+    /// The timeouts set on the socket, to report their expiry as `Error::Timeout`.
+    timeouts: Timeouts,
+}
+
+impl<T: Read + Write> InteractiveConnection<T> {
+    /// Switch a new connection into prompt mode.
+    pub(crate) fn enter(
+        socket: T,
+        reader: BufReader<T>,
+        deadline: Option<Deadline<T>>,
+        timeouts: Timeouts,
+    ) -> Result<Self, Error> {
+        let mut state = PromptState {
+            socket,
+            reader,
+            response: None,
+            awaiting_response: false,
+            broken: false,
+            end: deadline.as_ref().map(Deadline::end),
+            deadline,
+        };
+
+        state
+            .limit_write()
+            .and_then(|()| commands::prompt(&mut state.socket))
+            .and_then(|()| commands::end(&mut state.socket))
+            .map_err(|err| timeouts.error(err, timeouts.write))?;
+        state
+            .read_response()
+            .map_err(|err| timeouts.error(err, timeouts.read))?;
+
+        Ok(Self {
+            state: RefCell::new(state),
+            timeouts,
+        })
+    }
+
+    /// Get a connection for issuing the next command.
+    ///
+    /// Any unread part of the previous response is discarded. If the builder set a deadline, the
+    /// command must complete within it, starting from this call.
+    pub fn command(&mut self) -> Connection<PromptStream<'_, T>> {
+        let state = self.state.get_mut();
+        state.response = None;
+        state.end = state.deadline.as_ref().map(Deadline::end);
+
+        let stream = PromptStream { state: &self.state };
+        Connection::new(stream.clone(), BufReader::new(stream)).with_timeouts(self.timeouts)
+    }
+}
+
+#[derive(Debug)]
+struct PromptState<T> {
+    socket: T,
+    reader: BufReader<T>,
+    /// The response to the last command, once it has been read up to the prompt.
+    response: Option<Cursor<Vec<u8>>>,
+    /// Whether a command has been written since the last response was read.
+    awaiting_response: bool,
+    /// Whether an I/O error left the connection at an unknown point in the output.
+    broken: bool,
+    deadline: Option<Deadline<T>>,
+    /// The time by which the current command must complete, if a deadline is configured.
+    end: Option<Instant>,
+}
+
+impl<T: Read + Write> PromptState<T> {
+    /// Fail if an earlier error broke the connection, and mark it broken if `op` fails.
+    fn guard<R, F>(&mut self, op: F) -> io::Result<R>
+    where
+        F: FnOnce(&mut Self) -> io::Result<R>,
+    {
+        if self.broken {
+            return Err(io::Error::other(
+                "connection is unusable after an earlier error",
+            ));
+        }
+
+        let result = op(self);
+        self.broken = result.is_err();
+        result
+    }
+
+    /// Shorten the read timeout to the time left for the current command.
+    fn limit_read(&self) -> io::Result<()> {
+        match (&self.deadline, self.end) {
+            (Some(deadline), Some(end)) => deadline.limit_read(self.reader.get_ref(), end),
+            _ => Ok(()),
+        }
+    }
+
+    /// Shorten the write timeout to the time left for the current command.
+    fn limit_write(&self) -> io::Result<()> {
+        match (&self.deadline, self.end) {
+            (Some(deadline), Some(end)) => deadline.limit_write(&self.socket, end),
+            _ => Ok(()),
+        }
+    }
+
+    /// Read up to the next prompt, returning the response without the prompt.
+    fn read_response(&mut self) -> io::Result<Vec<u8>> {
+        let mut prompt = PromptScanner::default();
+        let mut response = Vec::new();
+
+        // Only consume output up to the prompt, leaving anything after it buffered.
+        while !prompt.is_complete() {
+            self.limit_read()?;
+            let data = self.reader.fill_buf()?;
+            if data.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "connection closed before the prompt",
+                ));
+            }
+
+            let len = match prompt.scan(data) {
+                Progress::Complete(len) => len,
+                Progress::Incomplete => data.len(),
+            };
+            response.extend_from_slice(&data[..len]);
+            self.reader.consume(len);
+        }
+
+        self.awaiting_response = false;
+        response.truncate(response.len() - 2);
+        Ok(response)
+    }
+}
+
+/// A transport which reads a single response from an [`InteractiveConnection`].
+///
+/// Writes go directly to the underlying connection. Reads return the response to the command
+/// written, ending at the prompt as if HAProxy had closed the connection.
+#[derive(Debug)]
+pub struct PromptStream<'a, T> {
+    state: &'a RefCell<PromptState<T>>,
+}
+
+impl<T> Clone for PromptStream<'_, T> {
+    fn clone(&self) -> Self {
+        Self { state: self.state }
+    }
+}
+
+impl<T: Read + Write> Read for PromptStream<'_, T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut state = self.state.borrow_mut();
+
+        if state.response.is_none() {
+            let response = if state.awaiting_response {
+                state.guard(|state| state.read_response())?
+            } else {
+                Vec::new()
+            };
+            state.response = Some(Cursor::new(response));
+        }
+
+        match state.response.as_mut() {
+            Some(response) => response.read(buf),
+            None => Ok(0),
+        }
+    }
+}
+
+impl<T: Read + Write> Write for PromptStream<'_, T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.state.borrow_mut().guard(|state| {
+            state.awaiting_response = true;
+            state.limit_write()?;
+            state.socket.write(buf)
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.state.borrow_mut().guard(|state| state.socket.flush())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockStream;
+    use crate::models::AclId;
+
+    #[test]
+    fn commands_share_connection() {
+        let stream =
+            MockStream::new(b"\n> \n> 0x1 127.0.0.1\n0x2 127.0.0.2\n\n> Key not found.\n\n> ");
+        let mut connection = InteractiveConnection::enter(
+            stream.clone(),
+            BufReader::new(stream.clone()),
+            None,
+            Timeouts::default(),
+        )
+        .unwrap();
+
+        connection
+            .command()
+            .acl_add(AclId::Id(0), "127.0.0.2")
+            .unwrap();
+        let entries = connection
+            .command()
+            .acl_data::<String>(AclId::Id(0))
+            .unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(
+            connection
+                .command()
+                .acl_del(AclId::Id(0), crate::requests::EntryRef::Key("a")),
+            Err(Error::NotFound)
+        ));
+
+        assert_eq!(
+            stream.output.borrow().as_slice(),
+            &b"prompt\nadd acl #0 127.0.0.2\nshow acl #0\ndel acl #0 a\n"[..]
+        );
+    }
+
+    #[test]
+    fn unread_response_is_discarded() {
+        let stream = MockStream::new(b"> Unexpected\nextra lines\n\n> \n> ");
+        let mut connection = InteractiveConnection::enter(
+            stream.clone(),
+            BufReader::new(stream.clone()),
+            None,
+            Timeouts::default(),
+        )
+        .unwrap();
+
+        // Only the first line of the response is read; the rest must not leak into the next one.
+        assert!(connection.command().acl_clear(AclId::Id(0)).is_err());
+        connection.command().acl_clear(AclId::Id(0)).unwrap();
+    }
+
+    #[test]
+    fn closed_connection_is_reported() {
+        let stream = MockStream::new(b"> \n");
+        let mut connection = InteractiveConnection::enter(
+            stream.clone(),
+            BufReader::new(stream.clone()),
+            None,
+            Timeouts::default(),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            connection.command().acl_clear(AclId::Id(0)),
+            Err(Error::IoError(_))
+        ));
+    }
+
+    #[test]
+    fn error_breaks_connection() {
+        let stream = MockStream::stalling(b"> 0x1 127.0.0.1\n");
+        let mut connection = InteractiveConnection::enter(
+            stream.clone(),
+            BufReader::new(stream.clone()),
+            None,
+            Timeouts::default(),
+        )
+        .unwrap();
+
+        assert!(connection
+            .command()
+            .acl_data::<String>(AclId::Id(0))
+            .is_err());
+
+        // The rest of the response arrives late, and must not be taken for the next response.
+        stream
+            .input
+            .borrow_mut()
+            .get_mut()
+            .extend_from_slice(b"\n> \n> ");
+        assert!(matches!(
+            connection.command().acl_clear(AclId::Id(0)),
+            Err(Error::IoError(_))
+        ));
+        assert_eq!(
+            stream.output.borrow().as_slice(),
+            &b"prompt\nshow acl #0\n"[..]
+        );
+    }
+}
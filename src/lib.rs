@@ -29,6 +29,18 @@
 //! let connection_builder = TcpSocketBuilder::default();
 //! ```
 //!
+//! By default, connections wait indefinitely for HAProxy. Both builders accept read and write
+//! timeouts, plus an overall deadline for each command, which fail with
+//! [`errors::Error::Timeout`] when exceeded. A connect timeout makes `.connect()` fail with an I/O
+//! error of kind [`std::io::ErrorKind::TimedOut`].
+//! ```
+//! # use haptik::UnixSocketBuilder;
+//! use std::time::Duration;
+//! let connection_builder = UnixSocketBuilder::default()
+//!     .connect_timeout(Duration::from_secs(1))
+//!     .deadline(Duration::from_secs(5));
+//! ```
+//!
 //! # Issuing Commands
 //! Calling `.connect()` on a [`ConnectionBuilder`] yields a [`Connection`] you can use to issue
 //! a single command to HAProxy. Generally, you will interact with an abstraction over a
@@ -42,15 +54,53 @@
 //! let backend_error_count = connection.errors().expect("Failed to query backend error count");
 //! println!("Total errors across all backends: {}", backend_error_count);
 //! ```
+//!
+//! To issue many commands without reconnecting each time, switch a connection into HAProxy's
+//! interactive mode with [`Connection::interactive`].
+//!
+//! ```no_run
+//! # use haptik::{ConnectionBuilder, UnixSocketBuilder};
+//! let connection_builder = UnixSocketBuilder::default();
+//! let connection = connection_builder.connect().expect("Failed to connect to HAProxy");
+//! let mut connection = connection.interactive().expect("Failed to enter interactive mode");
+//! let errors = connection.command().errors().expect("Failed to query error count");
+//! let info = connection.command().info().expect("Failed to query process information");
+//! ```
+//!
+//! Alternatively, queue commands in a [`batch::Batch`] and send them all in one request with
+//! [`Connection::batch`].
+//!
+//! Commands are encoded and their responses parsed by the transport-independent [`protocol`]
+//! module, which can also be used to drive HAProxy's CLI from your own I/O.
+//!
+//! # Features
+//! - `async`: issue commands from tokio-based code using [`AsyncConnection`], created by the same
+//!   builders through [`AsyncConnectionBuilder::connect_async`]. This adds a dependency on `tokio`.
+//! - `json`: support HAProxy's JSON output via [`Connection::stats_json`],
+//!   [`Connection::info_json`] and [`Connection::schema_json`]. This adds a dependency on
+//!   `serde_json`.
 
 #![forbid(unsafe_code)]
 
+#[cfg(feature = "async")]
+pub mod async_connection;
+pub mod batch;
 mod commands;
 pub mod connection;
 pub mod errors;
+pub mod interactive;
+pub mod lifecycle;
+#[cfg(test)]
+mod mock;
 pub mod models;
 mod parsers;
+pub mod protocol;
 pub mod requests;
 pub mod responses;
+pub mod sync;
+pub mod transaction;
 
+#[cfg(feature = "async")]
+pub use async_connection::{AsyncConnection, AsyncConnectionBuilder};
 pub use connection::{Connection, ConnectionBuilder, TcpSocketBuilder, UnixSocketBuilder};
+pub use interactive::InteractiveConnection;
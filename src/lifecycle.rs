@@ -0,0 +1,148 @@
+//! Safely remove servers added at runtime.
+//!
+//! HAProxy only deletes a server which is in maintenance mode and no longer has connections.
+//! [`remove_server`] checks both conditions before issuing `del server`, so a server which is still
+//! serving traffic is never removed by mistake.
+
+use std::io::{Read, Write};
+
+use crate::connection::{Connection, ConnectionBuilder};
+use crate::errors::Error;
+use crate::requests::{BackendId, ServerId, StatFilter, StatObjects};
+use crate::responses::StatRow;
+
+/// Delete a server once it is in maintenance mode and has no remaining connections.
+///
+/// Returns `Error::InvalidState` without deleting the server if it is not in maintenance mode, or
+/// if it still has current or queued sessions, and `Error::UnknownId` if the server does not
+/// exist. A server which was just put into maintenance may need time for its sessions to finish,
+/// so callers can retry on `Error::InvalidState`.
+///
+/// The checks and the deletion are separate commands, so HAProxy makes the final decision: it
+/// still refuses to delete a server whose state changed after it was checked.
+///
+/// # Examples
+/// ```no_run
+/// use haptik::{ConnectionBuilder, UnixSocketBuilder};
+/// use haptik::lifecycle::remove_server;
+/// use haptik::requests::{BackendId, ServerId, ServerState};
+///
+/// let socket_builder = UnixSocketBuilder::default();
+/// socket_builder
+///     .connect()
+///     .expect("Failed to connect")
+///     .set_server_state(BackendId::Name("servers"), ServerId::Name("server2"), ServerState::Maint)
+///     .expect("Failed to put server into maintenance");
+/// remove_server(&socket_builder, BackendId::Name("servers"), ServerId::Name("server2"))
+///     .expect("Failed to remove server");
+/// ```
+pub fn remove_server<B, T>(builder: &B, backend: BackendId, server: ServerId) -> Result<(), Error>
+where
+    B: ConnectionBuilder<Connection = Connection<T>>,
+    T: Read + Write,
+{
+    if backend == BackendId::All {
+        return Err(Error::InvalidArgument);
+    }
+
+    let filter = StatFilter {
+        proxy: backend.clone(),
+        objects: StatObjects::SERVER,
+        server: match server {
+            ServerId::Id(id) => Some(id),
+            ServerId::Name(_) => None,
+        },
+    };
+    let stats = builder
+        .connect()?
+        .stats_filtered(filter)?
+        .into_iter()
+        .find(|row| is_server(row, &server))
+        .ok_or(Error::UnknownId)?;
+
+    // HAProxy reports servers in maintenance as `MAINT`, followed by the reason if inherited.
+    let in_maintenance = stats
+        .status
+        .as_deref()
+        .is_some_and(|status| status.starts_with("MAINT"));
+    if !in_maintenance || stats.scur.unwrap_or(0) > 0 || stats.qcur.unwrap_or(0) > 0 {
+        return Err(Error::InvalidState);
+    }
+
+    builder.connect()?.del_server(backend, server)
+}
+
+/// Whether `row` describes `server`.
+fn is_server(row: &StatRow, server: &ServerId) -> bool {
+    match server {
+        ServerId::Id(id) => row.sid.map(i64::from) == Some(i64::from(*id)),
+        ServerId::Name(name) => row.svname == *name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockBuilder;
+
+    #[test]
+    fn removes_idle_server_in_maintenance() {
+        let builder = MockBuilder::new(&[
+            b"# pxname,svname,qcur,scur,status,sid,\nservers,server2,0,0,MAINT,2,\n\n",
+            b"Server deleted.\n\n",
+        ]);
+        remove_server(
+            &builder,
+            BackendId::Name("servers"),
+            ServerId::Name("server2"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            builder.output.borrow().as_slice(),
+            &b"show stat servers 4 -1\ndel server servers/server2\n"[..]
+        );
+    }
+
+    #[test]
+    fn keeps_server_in_service() {
+        let builder = MockBuilder::new(&[
+            b"# pxname,svname,qcur,scur,status,sid,\nservers,server1,0,0,UP,1,\n\n",
+        ]);
+        assert!(matches!(
+            remove_server(&builder, BackendId::Id(3), ServerId::Id(1)),
+            Err(Error::InvalidState)
+        ));
+        assert_eq!(
+            builder.output.borrow().as_slice(),
+            &b"show stat 3 4 1\n"[..]
+        );
+    }
+
+    #[test]
+    fn keeps_server_with_sessions() {
+        let builder = MockBuilder::new(&[
+            b"# pxname,svname,qcur,scur,status,sid,\nservers,server2,0,4,MAINT,2,\n\n",
+        ]);
+        assert!(matches!(
+            remove_server(&builder, BackendId::Name("servers"), ServerId::Id(2)),
+            Err(Error::InvalidState)
+        ));
+        assert!(!String::from_utf8_lossy(&builder.output.borrow()).contains("del server"));
+    }
+
+    #[test]
+    fn reports_unknown_server() {
+        let builder = MockBuilder::new(&[
+            b"# pxname,svname,qcur,scur,status,sid,\nservers,server2,0,0,MAINT,2,\n\n",
+        ]);
+        assert!(matches!(
+            remove_server(
+                &builder,
+                BackendId::Name("servers"),
+                ServerId::Name("server9")
+            ),
+            Err(Error::UnknownId)
+        ));
+    }
+}
@@ -0,0 +1,89 @@
+//! Transports replaying canned HAProxy output, for tests.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{self, BufReader, Cursor, Read, Write};
+use std::rc::Rc;
+
+use crate::connection::{Connection, ConnectionBuilder};
+
+/// A transport replaying canned output and recording commands written to it.
+#[derive(Clone, Default)]
+pub(crate) struct MockStream {
+    pub(crate) input: Rc<RefCell<Cursor<Vec<u8>>>>,
+    pub(crate) output: Rc<RefCell<Vec<u8>>>,
+    /// Report `WouldBlock` instead of end of file once the input is used up, like a socket whose
+    /// read timeout expired.
+    pub(crate) stall: bool,
+}
+
+impl MockStream {
+    pub(crate) fn new(input: &[u8]) -> Self {
+        Self {
+            input: Rc::new(RefCell::new(Cursor::new(input.to_vec()))),
+            output: Rc::default(),
+            stall: false,
+        }
+    }
+
+    /// A stream which stalls once `input` is used up.
+    pub(crate) fn stalling(input: &[u8]) -> Self {
+        Self {
+            stall: true,
+            ..Self::new(input)
+        }
+    }
+}
+
+impl Read for MockStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut input = self.input.borrow_mut();
+        if self.stall && input.position() >= input.get_ref().len() as u64 {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        input.read(buf)
+    }
+}
+
+impl Write for MockStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Opens connections which reply with each of `responses` in turn, recording every command.
+pub(crate) struct MockBuilder {
+    responses: RefCell<VecDeque<Vec<u8>>>,
+    pub(crate) output: Rc<RefCell<Vec<u8>>>,
+}
+
+impl MockBuilder {
+    pub(crate) fn new(responses: &[&[u8]]) -> Self {
+        Self {
+            responses: RefCell::new(responses.iter().map(|response| response.to_vec()).collect()),
+            output: Rc::default(),
+        }
+    }
+}
+
+impl ConnectionBuilder for MockBuilder {
+    type Connection = Connection<MockStream>;
+
+    fn connect(&self) -> Result<Self::Connection, io::Error> {
+        let response =
+            self.responses.borrow_mut().pop_front().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::ConnectionRefused, "no response left")
+            })?;
+        let stream = MockStream {
+            input: Rc::new(RefCell::new(Cursor::new(response))),
+            output: self.output.clone(),
+            stall: false,
+        };
+
+        Ok(Connection::new(stream.clone(), BufReader::new(stream)))
+    }
+}
@@ -1,5 +1,7 @@
 use crate::errors::Error;
 use std::fmt::{self, Display};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::PathBuf;
 use std::str::FromStr;
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -17,9 +19,9 @@ impl<T: FromStr> FromStr for AclEntry<T> {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let parts: Vec<&str> = s.splitn(2, ' ').collect();
         if let [id, value] = parts.as_slice() {
-            if id.starts_with("0x") {
+            if let Some(id) = id.strip_prefix("0x") {
                 Ok(Self {
-                    id: u64::from_str_radix(&id[2..], 16)?,
+                    id: u64::from_str_radix(id, 16)?,
                     value: T::from_str(value).map_err(|_| Error::ParseFailure)?,
                 })
             } else {
@@ -31,15 +33,156 @@ impl<T: FromStr> FromStr for AclEntry<T> {
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MapEntry<K, V> {
+    /// Element pointer within HAProxy. Forcing u64 instead of usize to allow running haptik on
+    /// 32-bit machines when HAProxy pointers are 64-bits.
+    pub id: u64,
+    /// The key of the map entry.
+    pub key: K,
+    /// The value the key maps to.
+    pub value: V,
+}
+
+impl<K: FromStr, V: FromStr> FromStr for MapEntry<K, V> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.splitn(3, ' ').collect();
+        if let [id, key, value] = parts.as_slice() {
+            if let Some(id) = id.strip_prefix("0x") {
+                Ok(Self {
+                    id: u64::from_str_radix(id, 16)?,
+                    key: K::from_str(key).map_err(|_| Error::ParseFailure)?,
+                    value: V::from_str(value).map_err(|_| Error::ParseFailure)?,
+                })
+            } else {
+                Err(Error::ParseFailure)
+            }
+        } else {
+            Err(Error::ParseFailure)
+        }
+    }
+}
+
+/// Identifier of an HAProxy ACL.
+///
+/// Numeric IDs may change when the configuration changes; ACLs loaded from a file can also be
+/// referenced by the file name, which is stable.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum AclId {
+    /// The numeric ID of the ACL, as reported by `show acl`.
     Id(i32),
+    /// The file the ACL was loaded from.
+    File(PathBuf),
 }
 
 impl Display for AclId {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             AclId::Id(id) => f.write_fmt(format_args!("#{}", id)),
+            AclId::File(path) => path.display().fmt(f),
+        }
+    }
+}
+
+/// Identifier of an HAProxy map.
+///
+/// Numeric IDs may change when the configuration changes; maps can also be referenced by the file
+/// they were loaded from, which is stable.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum MapId {
+    /// The numeric ID of the map, as reported by `show map`.
+    Id(i32),
+    /// The file the map was loaded from.
+    File(PathBuf),
+}
+
+impl Display for MapId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapId::Id(id) => f.write_fmt(format_args!("#{}", id)),
+            MapId::File(path) => path.display().fmt(f),
+        }
+    }
+}
+
+/// The type of the keys of a stick table.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StickTableType {
+    Ip,
+    Ipv6,
+    Integer,
+    String,
+    Binary,
+}
+
+impl FromStr for StickTableType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ip" => Ok(StickTableType::Ip),
+            "ipv6" => Ok(StickTableType::Ipv6),
+            "integer" => Ok(StickTableType::Integer),
+            "string" => Ok(StickTableType::String),
+            "binary" => Ok(StickTableType::Binary),
+            _ => Err(Error::ParseFailure),
+        }
+    }
+}
+
+/// The key of a stick table entry.
+///
+/// Keys are formatted the way HAProxy expects them in `key <key>` arguments; binary keys are
+/// written as hexadecimal.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum StickTableKey {
+    Ip(Ipv4Addr),
+    Ipv6(Ipv6Addr),
+    Integer(u32),
+    String(String),
+    Binary(Vec<u8>),
+}
+
+impl StickTableKey {
+    /// Parse a key as reported for a table of the given type.
+    pub fn parse(key_type: StickTableType, s: &str) -> Result<Self, Error> {
+        let parse_err = |_| Error::ParseFailure;
+
+        match key_type {
+            StickTableType::Ip => Ipv4Addr::from_str(s)
+                .map(StickTableKey::Ip)
+                .map_err(parse_err),
+            StickTableType::Ipv6 => Ipv6Addr::from_str(s)
+                .map(StickTableKey::Ipv6)
+                .map_err(parse_err),
+            StickTableType::Integer => Ok(StickTableKey::Integer(u32::from_str(s)?)),
+            StickTableType::String => Ok(StickTableKey::String(s.to_string())),
+            StickTableType::Binary => {
+                if s.len() % 2 != 0 || !s.is_ascii() {
+                    return Err(Error::ParseFailure);
+                }
+                (0..s.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(Error::from))
+                    .collect::<Result<_, _>>()
+                    .map(StickTableKey::Binary)
+            }
+        }
+    }
+}
+
+impl Display for StickTableKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StickTableKey::Ip(addr) => addr.fmt(f),
+            StickTableKey::Ipv6(addr) => addr.fmt(f),
+            StickTableKey::Integer(key) => key.fmt(f),
+            StickTableKey::String(key) => f.write_str(key),
+            StickTableKey::Binary(key) => key
+                .iter()
+                .try_for_each(|byte| f.write_fmt(format_args!("{:02x}", byte))),
         }
     }
 }
@@ -58,4 +201,70 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn mapentry_from_str() {
+        assert_eq!(
+            MapEntry::<String, u32>::from_str("0x1234 example.com 10").unwrap(),
+            MapEntry::<String, u32> {
+                id: 0x1234,
+                key: "example.com".into(),
+                value: 10,
+            }
+        );
+        assert_eq!(
+            MapEntry::<String, String>::from_str("0x1234 key value with spaces").unwrap(),
+            MapEntry::<String, String> {
+                id: 0x1234,
+                key: "key".into(),
+                value: "value with spaces".into(),
+            }
+        );
+        MapEntry::<String, String>::from_str("0x1234 key")
+            .expect_err("Parsed a map entry without a value");
+        MapEntry::<String, u32>::from_str("0x1234 key value")
+            .expect_err("Parsed a map entry with an invalid value");
+    }
+
+    #[test]
+    fn aclid_display() {
+        assert_eq!(AclId::Id(0).to_string(), "#0");
+        assert_eq!(
+            AclId::File("/etc/haproxy/blocklist.acl".into()).to_string(),
+            "/etc/haproxy/blocklist.acl"
+        );
+    }
+
+    #[test]
+    fn mapid_display() {
+        assert_eq!(MapId::Id(3).to_string(), "#3");
+        assert_eq!(
+            MapId::File("/etc/haproxy/hosts.map".into()).to_string(),
+            "/etc/haproxy/hosts.map"
+        );
+    }
+
+    #[test]
+    fn stick_table_key_parse() {
+        assert_eq!(
+            StickTableKey::parse(StickTableType::Ip, "127.0.0.1").unwrap(),
+            StickTableKey::Ip(Ipv4Addr::LOCALHOST)
+        );
+        assert_eq!(
+            StickTableKey::parse(StickTableType::Integer, "42").unwrap(),
+            StickTableKey::Integer(42)
+        );
+        assert_eq!(
+            StickTableKey::parse(StickTableType::Binary, "00ff10").unwrap(),
+            StickTableKey::Binary(vec![0x00, 0xff, 0x10])
+        );
+        StickTableKey::parse(StickTableType::Binary, "0ff").expect_err("Parsed an odd length key");
+        StickTableKey::parse(StickTableType::Ip, "::1").expect_err("Parsed IPv6 as IPv4");
+    }
+
+    #[test]
+    fn stick_table_key_display() {
+        assert_eq!(StickTableKey::Binary(vec![0x00, 0xff]).to_string(), "00ff");
+        assert_eq!(StickTableKey::Ipv6(Ipv6Addr::LOCALHOST).to_string(), "::1");
+    }
 }
@@ -3,7 +3,12 @@ use std::str::FromStr;
 
 use crate::errors::Error;
 use crate::models;
-use crate::responses::{Acl, CliSocket};
+use crate::responses::{
+    Acl, AddrChange, CliSocket, Info, Level, Map, ServerConnections, ServerStateRow, Session,
+    StatRow, StateChange, StickTable, StickTableEntry, TypedInfo, TypedStat,
+};
+#[cfg(feature = "json")]
+use crate::responses::{FieldNature, FieldOrigin, FieldScope, FieldTags, Tagged};
 
 pub fn parse_acl_add<T: Read>(reader: &mut BufReader<T>) -> Result<(), Error> {
     let mut buf = String::new();
@@ -50,6 +55,150 @@ pub fn parse_acl_entries<T: Read, E: FromStr>(
         .collect()
 }
 
+pub fn parse_addr_change<T: Read>(reader: &mut BufReader<T>) -> Result<AddrChange, Error> {
+    let mut buf = String::new();
+    reader.read_line(&mut buf)?;
+    let message = buf.trim_end();
+
+    if message.starts_with("IP changed from ") || message.starts_with("no need to change the addr")
+    {
+        AddrChange::from_str(message)
+    } else if message.is_empty() {
+        Err(Error::ParseFailure)
+    } else {
+        Err(error_from_message(message))
+    }
+}
+
+pub fn parse_map_list<T: Read>(reader: &mut BufReader<T>) -> Result<Vec<Map>, Error> {
+    skip_comment_or_empty_lines(reader.lines())
+        .map(|line_res| {
+            line_res
+                .map_err(Error::from)
+                .and_then(|line| Map::from_str(line.as_str()))
+        })
+        .collect()
+}
+
+pub fn parse_map_entries<T: Read, K: FromStr, V: FromStr>(
+    reader: &mut BufReader<T>,
+) -> Result<Vec<models::MapEntry<K, V>>, Error> {
+    let mut lines = skip_comment_or_empty_lines(reader.lines()).peekable();
+
+    if let Some(Ok(line)) = lines.peek() {
+        if line.starts_with("Unknown map identifier") {
+            return Err(Error::UnknownId);
+        }
+    }
+
+    lines
+        .map(|line_res| {
+            line_res
+                .map_err(Error::from)
+                .and_then(|line| models::MapEntry::from_str(line.as_str()))
+        })
+        .collect()
+}
+
+/// Parse the response to `get acl` or `get map` into an `AclMatch` or `MapMatch`.
+///
+/// HAProxy reports one line per expression using the ACL or map; only the first is parsed.
+pub fn parse_match<T: Read, M: FromStr<Err = Error>>(
+    reader: &mut BufReader<T>,
+) -> Result<M, Error> {
+    let line = skip_comment_or_empty_lines(reader.lines())
+        .next()
+        .ok_or(Error::ParseFailure)??;
+
+    if line.starts_with("type=") {
+        M::from_str(line.as_str())
+    } else {
+        Err(error_from_message(line.as_str()))
+    }
+}
+
+/// Parse the response to `show cli level`.
+pub fn parse_level<T: Read>(reader: &mut BufReader<T>) -> Result<Level, Error> {
+    let mut buf = String::new();
+    reader.read_line(&mut buf)?;
+    buf.pop(); // Remove trailing '\n'
+
+    Level::from_str(buf.as_str())
+}
+
+/// Parse the response to `prepare acl` or `prepare map` into the new version number.
+pub fn parse_prepare<T: Read>(reader: &mut BufReader<T>) -> Result<u32, Error> {
+    let mut buf = String::new();
+    reader.read_line(&mut buf)?;
+
+    match buf.trim_end().strip_prefix("New version created: ") {
+        Some(version) => u32::from_str(version).map_err(|_| Error::ParseFailure),
+        None if buf.trim_end().is_empty() => Err(Error::ParseFailure),
+        None => Err(error_from_message(buf.trim_end())),
+    }
+}
+
+/// Parse the response to a command which replies with `confirmation` on success.
+pub fn parse_confirmation<T: Read>(
+    reader: &mut BufReader<T>,
+    confirmation: &str,
+) -> Result<(), Error> {
+    let mut buf = String::new();
+    reader.read_line(&mut buf)?;
+
+    match buf.trim_end() {
+        message if message == confirmation => Ok(()),
+        "" => Err(Error::ParseFailure),
+        message => Err(error_from_message(message)),
+    }
+}
+
+/// Parse the response to a command which produces no output on success.
+pub fn parse_empty_response<T: Read>(reader: &mut BufReader<T>) -> Result<(), Error> {
+    let mut buf = String::new();
+    reader.read_line(&mut buf)?;
+
+    if buf == "\n" {
+        Ok(())
+    } else if buf.is_empty() {
+        Err(Error::ParseFailure)
+    } else {
+        Err(error_from_message(buf.trim_end()))
+    }
+}
+
+pub fn parse_backends<T: Read>(reader: &mut BufReader<T>) -> Result<Vec<String>, Error> {
+    skip_comment_or_empty_lines(reader.lines())
+        .map(|line_res| {
+            line_res.map_err(Error::from).and_then(|line| {
+                // Proxy names cannot contain spaces, unlike error messages.
+                if line.contains(char::is_whitespace) {
+                    Err(error_from_message(line.as_str()))
+                } else {
+                    Ok(line)
+                }
+            })
+        })
+        .collect()
+}
+
+/// Parse the response to a command which produces no output when it changes an object's state,
+/// and replies with `unchanged` if the object was already in that state.
+pub fn parse_state_change<T: Read>(
+    reader: &mut BufReader<T>,
+    unchanged: &str,
+) -> Result<StateChange, Error> {
+    let mut buf = String::new();
+    reader.read_line(&mut buf)?;
+
+    match buf.trim_end() {
+        _ if buf == "\n" => Ok(StateChange::Applied),
+        "" => Err(Error::ParseFailure),
+        message if message == unchanged => Ok(StateChange::Unchanged),
+        message => Err(error_from_message(message)),
+    }
+}
+
 pub fn parse_cli_sockets<T: Read>(reader: &mut BufReader<T>) -> Result<Vec<CliSocket>, Error> {
     skip_comment_or_empty_lines(reader.lines())
         // Convert io::Error to Error. In the Ok case, pass the line to CliSocket::from_str.
@@ -66,12 +215,322 @@ pub fn parse_errors<T: Read>(reader: &mut BufReader<T>) -> Result<u32, Error> {
     reader.read_line(&mut buf)?;
     buf.pop(); // Remove trailing '\n'
 
-    buf.rsplitn(2, ' ')
+    buf.rsplit(' ')
         .next()
         .ok_or(Error::ParseFailure)
         .and_then(|count| u32::from_str(count).map_err(|_| Error::ParseFailure))
 }
 
+pub fn parse_info<T: Read>(reader: &mut BufReader<T>) -> Result<Info, Error> {
+    let lines = skip_comment_or_empty_lines(reader.lines()).collect::<Result<Vec<_>, _>>()?;
+
+    let fields = lines
+        .iter()
+        .map(|line| {
+            let mut parts = line.splitn(2, ':');
+            match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) => Ok((key, value.trim_start())),
+                _ => Err(Error::ParseFailure),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Info::from_fields(fields)
+}
+
+pub fn parse_info_typed<T: Read>(reader: &mut BufReader<T>) -> Result<Vec<TypedInfo>, Error> {
+    skip_comment_or_empty_lines(reader.lines())
+        .map(|line_res| {
+            line_res
+                .map_err(Error::from)
+                .and_then(|line| TypedInfo::from_str(line.as_str()))
+        })
+        .collect()
+}
+
+pub fn parse_stats<T: Read>(reader: &mut BufReader<T>) -> Result<Vec<StatRow>, Error> {
+    let mut lines = reader.lines();
+
+    // The first line names the columns; HAProxy versions differ in which columns are present.
+    let header = lines.next().ok_or(Error::ParseFailure)??;
+    let columns = split_csv(header.strip_prefix("# ").ok_or(Error::ParseFailure)?);
+
+    skip_comment_or_empty_lines(lines)
+        .map(|line_res| {
+            line_res.map_err(Error::from).and_then(|line| {
+                let values = split_csv(line.as_str());
+                StatRow::from_fields(
+                    columns
+                        .iter()
+                        .map(String::as_str)
+                        .zip(values.iter().map(String::as_str)),
+                )
+            })
+        })
+        .collect()
+}
+
+pub fn parse_servers_conn<T: Read>(
+    reader: &mut BufReader<T>,
+) -> Result<Vec<ServerConnections>, Error> {
+    let mut lines = reader.lines();
+
+    let header = lines.next().ok_or(Error::ParseFailure)??;
+    let header = match header.strip_prefix("# ") {
+        Some(header) => header,
+        None if header.is_empty() => return Err(Error::ParseFailure),
+        None => return Err(error_from_message(&header)),
+    };
+    // The last column, such as `idle_per_thr[4]`, repeats once for each thread.
+    let columns: Vec<&str> = header
+        .split(' ')
+        .map(|column| column.split('[').next().unwrap_or(column))
+        .collect();
+    let repeated = columns.last().copied().ok_or(Error::ParseFailure)?;
+
+    skip_comment_or_empty_lines(lines)
+        .map(|line_res| {
+            line_res.map_err(Error::from).and_then(|line| {
+                let names = columns[..columns.len() - 1]
+                    .iter()
+                    .copied()
+                    .chain(std::iter::repeat(repeated));
+                ServerConnections::from_fields(names.zip(line.split(' ')))
+            })
+        })
+        .collect()
+}
+
+pub fn parse_servers_state<T: Read>(
+    reader: &mut BufReader<T>,
+) -> Result<Vec<ServerStateRow>, Error> {
+    let mut lines = reader.lines();
+
+    // The first line holds the format version, which is followed by the column names.
+    let version = lines.next().ok_or(Error::ParseFailure)??;
+    match version.as_str() {
+        "1" => {}
+        // Empty output, or a format version this parser does not know.
+        version if version.is_empty() || u32::from_str(version).is_ok() => {
+            return Err(Error::ParseFailure)
+        }
+        message => return Err(error_from_message(message)),
+    }
+
+    let header = lines.next().ok_or(Error::ParseFailure)??;
+    let columns: Vec<&str> = header
+        .strip_prefix("# ")
+        .ok_or(Error::ParseFailure)?
+        .split(' ')
+        .collect();
+
+    skip_comment_or_empty_lines(lines)
+        .map(|line_res| {
+            line_res.map_err(Error::from).and_then(|line| {
+                ServerStateRow::from_fields(columns.iter().copied().zip(line.split(' ')))
+            })
+        })
+        .collect()
+}
+
+pub fn parse_sessions<T: Read>(reader: &mut BufReader<T>) -> Result<Vec<Session>, Error> {
+    skip_comment_or_empty_lines(reader.lines())
+        .map(|line_res| {
+            line_res
+                .map_err(Error::from)
+                .and_then(|line| Session::from_str(line.as_str()))
+        })
+        .collect()
+}
+
+/// Parse the detailed description of a single session, returning it unchanged.
+pub fn parse_session_detail<T: Read>(reader: &mut BufReader<T>) -> Result<String, Error> {
+    let mut buf = String::new();
+    reader.read_to_string(&mut buf)?;
+
+    let first_line = buf.lines().next().unwrap_or("");
+    if first_line.starts_with("0x") {
+        Ok(buf.trim_end().to_string())
+    } else if first_line.is_empty() {
+        Err(Error::ParseFailure)
+    } else {
+        Err(error_from_message(first_line))
+    }
+}
+
+pub fn parse_tables<T: Read>(reader: &mut BufReader<T>) -> Result<Vec<StickTable>, Error> {
+    reader
+        .lines()
+        .filter(|line_res| !matches!(line_res, Ok(line) if line.is_empty()))
+        .map(|line_res| {
+            line_res.map_err(Error::from).and_then(|line| {
+                if line.starts_with("# table: ") {
+                    StickTable::from_str(line.as_str())
+                } else {
+                    Err(error_from_message(line.as_str()))
+                }
+            })
+        })
+        .collect()
+}
+
+pub fn parse_table_entries<T: Read>(
+    reader: &mut BufReader<T>,
+) -> Result<Vec<StickTableEntry>, Error> {
+    let mut lines = reader.lines();
+
+    // The table description comes first, giving the type needed to parse keys.
+    let header = lines.next().ok_or(Error::ParseFailure)??;
+    let table = match header.as_str() {
+        "" => return Err(Error::ParseFailure),
+        header if header.starts_with("# table: ") => StickTable::from_str(header)?,
+        message => return Err(error_from_message(message)),
+    };
+
+    skip_comment_or_empty_lines(lines)
+        .map(|line_res| {
+            line_res
+                .map_err(Error::from)
+                .and_then(|line| StickTableEntry::parse(table.table_type, line.as_str()))
+        })
+        .collect()
+}
+
+pub fn parse_stats_typed<T: Read>(reader: &mut BufReader<T>) -> Result<Vec<TypedStat>, Error> {
+    skip_comment_or_empty_lines(reader.lines())
+        .map(|line_res| {
+            line_res
+                .map_err(Error::from)
+                .and_then(|line| TypedStat::from_str(line.as_str()))
+        })
+        .collect()
+}
+
+#[cfg(feature = "json")]
+pub fn parse_stats_json<T: Read>(reader: &mut BufReader<T>) -> Result<Vec<Tagged<StatRow>>, Error> {
+    let objects: serde_json::Value = serde_json::from_reader(reader)?;
+
+    objects
+        .as_array()
+        .ok_or(Error::ParseFailure)?
+        .iter()
+        .map(|object| {
+            let fields = object.as_array().ok_or(Error::ParseFailure)?;
+            parse_json_fields(fields, |fields| StatRow::from_fields(fields))
+        })
+        .collect()
+}
+
+#[cfg(feature = "json")]
+pub fn parse_info_json<T: Read>(reader: &mut BufReader<T>) -> Result<Tagged<Info>, Error> {
+    let fields: serde_json::Value = serde_json::from_reader(reader)?;
+
+    parse_json_fields(fields.as_array().ok_or(Error::ParseFailure)?, |fields| {
+        Info::from_fields(fields)
+    })
+}
+
+#[cfg(feature = "json")]
+pub fn parse_schema_json<T: Read>(reader: &mut BufReader<T>) -> Result<serde_json::Value, Error> {
+    Ok(serde_json::from_reader(reader)?)
+}
+
+/// Convert a list of JSON fields as emitted by `show stat json` and `show info json` into a typed
+/// value using `from_fields`, collecting the tags of each field.
+#[cfg(feature = "json")]
+fn parse_json_fields<V, F>(fields: &[serde_json::Value], from_fields: F) -> Result<Tagged<V>, Error>
+where
+    F: for<'a> FnOnce(Vec<(&'a str, &'a str)>) -> Result<V, Error>,
+{
+    let mut tags = std::collections::HashMap::with_capacity(fields.len());
+    let mut values = Vec::with_capacity(fields.len());
+
+    for field in fields {
+        let name = field["field"]["name"].as_str().ok_or(Error::ParseFailure)?;
+        let value = match &field["value"]["value"] {
+            serde_json::Value::String(value) => value.clone(),
+            serde_json::Value::Number(value) => value.to_string(),
+            _ => return Err(Error::ParseFailure),
+        };
+        let field_tags = FieldTags {
+            origin: FieldOrigin::from_str(json_str(&field["tags"]["origin"])?)?,
+            nature: FieldNature::from_str(json_str(&field["tags"]["nature"])?)?,
+            scope: FieldScope::from_str(json_str(&field["tags"]["scope"])?)?,
+        };
+
+        tags.insert(name.to_string(), field_tags);
+        values.push((name, value));
+    }
+
+    let value = from_fields(
+        values
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect(),
+    )?;
+
+    Ok(Tagged { value, tags })
+}
+
+#[cfg(feature = "json")]
+fn json_str(value: &serde_json::Value) -> Result<&str, Error> {
+    value.as_str().ok_or(Error::ParseFailure)
+}
+
+/// Split a line of HAProxy CSV output into its fields.
+///
+/// HAProxy quotes fields containing commas or quotes, escaping quotes by doubling them.
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
+
+/// Convert an error message reported by HAProxy into an `Error`.
+fn error_from_message(message: &str) -> Error {
+    if message.contains(" expects ") {
+        Error::MissingParameters
+    } else if (message.starts_with("Unknown ") && message.contains(" identifier"))
+        || message.starts_with("No such ")
+        || message == "Can't find backend."
+    {
+        Error::UnknownId
+    } else if message == "Key not found."
+        || message == "Pattern not found."
+        || message == "Session not found."
+    {
+        Error::NotFound
+    } else if message == "Value out of range."
+        || message == "Expects an integer value."
+        || message == "Integer value expected."
+    {
+        Error::InvalidArgument
+    } else if message.starts_with("Frontend was previously shut down")
+        || message.starts_with("Only servers in maintenance mode")
+        || message.starts_with("Server still has connections")
+    {
+        Error::InvalidState
+    } else {
+        Error::CommandFailed(message.to_string())
+    }
+}
+
 /// Skip lines starting with '#' and any line that is empty.
 fn skip_comment_or_empty_lines<B: io::BufRead>(
     lines: io::Lines<B>,
@@ -79,7 +538,7 @@ fn skip_comment_or_empty_lines<B: io::BufRead>(
     lines.filter(|line_res| {
         !line_res
             .as_ref()
-            .map(|line| line == "" || line.starts_with('#'))
+            .map(|line| line.is_empty() || line.starts_with('#'))
             .unwrap_or(true)
     })
 }
@@ -174,4 +633,512 @@ mod tests {
             BufReader::new(&b"Total events captured on [01/Jan/2020:03:15:05.071] : 100\n"[..]);
         assert_eq!(parse_errors(&mut buffer).unwrap(), 100);
     }
+
+    #[test]
+    fn parse_stats_valid_input() {
+        let mut buffer = BufReader::new(
+            &b"# pxname,svname,qcur,qmax,scur,smax,slim,stot,bin,bout,status,weight,type,check_status,hrsp_2xx,\n\
+http-in,FRONTEND,,,1,2,256,10,1024,2048,OPEN,,0,,9,\n\
+servers,server1,0,0,0,1,32,5,512,1024,UP,1,2,\"L4OK\",5,\n\
+servers,BACKEND,0,0,0,1,26,5,512,1024,UP,1,1,,5,\n\n"[..],
+        );
+        let rows = parse_stats(&mut buffer).expect("Failed to parse valid input");
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].pxname, "http-in");
+        assert_eq!(rows[0].svname, "FRONTEND");
+        assert_eq!(rows[0].stat_type, Some(responses::StatType::Frontend));
+        assert_eq!(rows[0].scur, Some(1));
+        assert_eq!(rows[0].qcur, None);
+        assert_eq!(rows[0].hrsp_2xx, Some(9));
+        assert_eq!(rows[1].svname, "server1");
+        assert_eq!(rows[1].status, Some("UP".into()));
+        assert_eq!(rows[1].weight, Some(1));
+        assert_eq!(rows[1].check_status, Some("L4OK".into()));
+        assert_eq!(rows[2].stat_type, Some(responses::StatType::Backend));
+
+        let mut buffer = BufReader::new(&b"Unknown command.\n"[..]);
+        parse_stats(&mut buffer).expect_err("Parsed stats without a header");
+    }
+
+    #[test]
+    fn split_csv_quoted_fields() {
+        assert_eq!(split_csv("a,,c"), vec!["a", "", "c"]);
+        assert_eq!(
+            split_csv("a,\"b,c\",\"d \"\"e\"\"\""),
+            vec!["a", "b,c", "d \"e\""]
+        );
+    }
+
+    #[test]
+    fn parse_info_valid_input() {
+        let mut buffer = BufReader::new(
+            &b"Name: HAProxy\nVersion: 2.2.4\nRelease_date: 2020/09/30\nPid: 1\nUptime_sec: 10\n\
+CurrConns: 1\nnode: 3a2f\ndescription: \n\n"[..],
+        );
+        let info = parse_info(&mut buffer).expect("Failed to parse valid input");
+
+        assert_eq!(info.name, Some("HAProxy".into()));
+        assert_eq!(info.release_date, Some("2020/09/30".into()));
+        assert_eq!(info.pid, Some(1));
+        assert_eq!(info.curr_conns, Some(1));
+        assert_eq!(info.node, Some("3a2f".into()));
+        assert_eq!(info.description, None);
+
+        let mut buffer = BufReader::new(&b"Unknown command\n"[..]);
+        parse_info(&mut buffer).expect_err("Parsed invalid info without error");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn parse_stats_json_valid_input() {
+        let mut buffer = BufReader::new(
+            &br#"[[
+{"objType":"Frontend","proxyId":2,"id":0,"field":{"pos":0,"name":"pxname"},"processNum":1,"tags":{"origin":"Key","nature":"Name","scope":"Service"},"value":{"type":"str","value":"http-in"}},
+{"objType":"Frontend","proxyId":2,"id":0,"field":{"pos":1,"name":"svname"},"processNum":1,"tags":{"origin":"Key","nature":"Name","scope":"Service"},"value":{"type":"str","value":"FRONTEND"}},
+{"objType":"Frontend","proxyId":2,"id":0,"field":{"pos":4,"name":"scur"},"processNum":1,"tags":{"origin":"Metric","nature":"Gauge","scope":"Process"},"value":{"type":"u32","value":3}},
+{"objType":"Frontend","proxyId":2,"id":0,"field":{"pos":7,"name":"stot"},"processNum":1,"tags":{"origin":"Metric","nature":"Counter","scope":"Process"},"value":{"type":"u64","value":12}}
+]]
+"#[..],
+        );
+        let rows = parse_stats_json(&mut buffer).expect("Failed to parse valid input");
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].value.pxname, "http-in");
+        assert_eq!(rows[0].value.svname, "FRONTEND");
+        assert_eq!(rows[0].value.scur, Some(3));
+        assert_eq!(rows[0].value.stot, Some(12));
+        assert_eq!(
+            rows[0].tags["scur"],
+            FieldTags {
+                origin: FieldOrigin::Metric,
+                nature: FieldNature::Gauge,
+                scope: FieldScope::Process,
+            }
+        );
+        assert_eq!(rows[0].tags["stot"].nature, FieldNature::Counter);
+
+        let mut buffer = BufReader::new(&b"Unknown command.\n"[..]);
+        parse_stats_json(&mut buffer).expect_err("Parsed invalid JSON without error");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn parse_info_json_valid_input() {
+        let mut buffer = BufReader::new(
+            &br#"[
+{"field":{"pos":0,"name":"Name"},"processNum":1,"tags":{"origin":"Product","nature":"Output","scope":"Service"},"value":{"type":"str","value":"HAProxy"}},
+{"field":{"pos":7,"name":"Uptime_sec"},"processNum":1,"tags":{"origin":"Metric","nature":"Duration","scope":"Process"},"value":{"type":"u32","value":10}}
+]
+"#[..],
+        );
+        let info = parse_info_json(&mut buffer).expect("Failed to parse valid input");
+
+        assert_eq!(info.value.name, Some("HAProxy".into()));
+        assert_eq!(info.value.uptime_sec, Some(10));
+        assert_eq!(info.tags["Name"].origin, FieldOrigin::Product);
+        assert_eq!(info.tags["Uptime_sec"].nature, FieldNature::Duration);
+    }
+
+    #[test]
+    fn parse_stats_typed_valid_input() {
+        let mut buffer = BufReader::new(
+            &b"F.2.0.0.pxname.1:KNS:str:http-in\nF.2.0.1.svname.1:KNS:str:FRONTEND\n\
+F.2.0.4.scur.1:MGP:u32:0\nF.2.0.4.scur.2:MGP:u32:3\n\n"[..],
+        );
+        let fields = parse_stats_typed(&mut buffer).expect("Failed to parse valid input");
+
+        assert_eq!(fields.len(), 4);
+        assert_eq!(fields[0].name, "pxname");
+        assert_eq!(
+            fields[0].value,
+            responses::TypedField::Str("http-in".into())
+        );
+        assert_eq!(fields[2].process, 1);
+        assert_eq!(fields[3].process, 2);
+        assert_eq!(fields[3].value, responses::TypedField::U32(3));
+        assert_eq!(fields[3].tags.nature, responses::FieldNature::Gauge);
+    }
+
+    #[test]
+    fn parse_info_typed_valid_input() {
+        let mut buffer = BufReader::new(
+            &b"0.Name.1:POS:str:HAProxy\n1.Version.1:POS:str:2.2.4\n7.Uptime_sec.1:MDP:u32:10\n\n"
+                [..],
+        );
+        let fields = parse_info_typed(&mut buffer).expect("Failed to parse valid input");
+
+        assert_eq!(fields.len(), 3);
+        assert_eq!(fields[1].name, "Version");
+        assert_eq!(fields[2].value, responses::TypedField::U32(10));
+        assert_eq!(fields[2].tags.nature, responses::FieldNature::Duration);
+    }
+
+    #[test]
+    fn parse_addr_change_responses() {
+        let mut buffer = BufReader::new(
+            &b"IP changed from '127.0.0.1' to '127.0.0.2', port changed from '8000' to '8001' by 'stats socket command'\n\n"[..],
+        );
+        let change = parse_addr_change(&mut buffer).unwrap();
+        assert_eq!(change.new_addr, Some("127.0.0.2".parse().unwrap()));
+        assert_eq!(change.new_port, Some(8001));
+
+        let mut buffer = BufReader::new(&b"No such server.\n\n"[..]);
+        assert!(matches!(
+            parse_addr_change(&mut buffer),
+            Err(Error::UnknownId)
+        ));
+    }
+
+    #[test]
+    fn parse_map_list_valid_input() {
+        let mut buffer = BufReader::new(&b"# id (file) description\n2 (/etc/haproxy/hosts.map) pattern loaded from file '/etc/haproxy/hosts.map' used by map at file '/etc/haproxy/haproxy.cfg' line 26\n\n"[..]);
+        let maps = parse_map_list(&mut buffer).unwrap();
+        assert_eq!(maps.len(), 1);
+        assert_eq!(maps[0].reference, Some("/etc/haproxy/hosts.map".into()));
+    }
+
+    #[test]
+    fn parse_map_entries_valid_input() {
+        let mut buffer = BufReader::new(
+            &b"0x55fd35e0e190 example.com server1\n0x55fd35e0e220 example.org server2\n\n"[..],
+        );
+        assert_eq!(
+            parse_map_entries::<_, String, String>(&mut buffer).unwrap(),
+            vec![
+                models::MapEntry {
+                    id: 0x55fd35e0e190,
+                    key: "example.com".into(),
+                    value: "server1".into(),
+                },
+                models::MapEntry {
+                    id: 0x55fd35e0e220,
+                    key: "example.org".into(),
+                    value: "server2".into(),
+                }
+            ]
+        );
+
+        let mut buffer =
+            BufReader::new(&b"Unknown map identifier. Please use #<id> or <file>.\n\n"[..]);
+        assert!(matches!(
+            parse_map_entries::<_, String, String>(&mut buffer),
+            Err(Error::UnknownId)
+        ));
+    }
+
+    #[test]
+    fn parse_match_responses() {
+        let mut buffer = BufReader::new(
+            &b"type=str, case=sensitive, found=yes, idx=tree, key=\"example.com\", value=\"server1\", type=\"str\"\n\n"[..],
+        );
+        let map_match: responses::MapMatch = parse_match(&mut buffer).unwrap();
+        assert!(map_match.found);
+        assert_eq!(map_match.value, Some("server1".into()));
+
+        let mut buffer =
+            BufReader::new(&b"Unknown map identifier. Please use #<id> or <file>.\n\n"[..]);
+        assert!(matches!(
+            parse_match::<_, responses::MapMatch>(&mut buffer),
+            Err(Error::UnknownId)
+        ));
+
+        let mut buffer = BufReader::new(
+            &b"type=ip, case=sensitive, match=yes, idx=tree, pattern=\"127.0.0.1\"\n\n"[..],
+        );
+        let acl_match: responses::AclMatch = parse_match(&mut buffer).unwrap();
+        assert!(acl_match.matched);
+        assert_eq!(acl_match.pattern, Some("127.0.0.1".into()));
+    }
+
+    #[test]
+    fn parse_level_responses() {
+        let mut buffer = BufReader::new(&b"operator\n"[..]);
+        assert_eq!(parse_level(&mut buffer).unwrap(), Level::Operator);
+
+        let mut buffer = BufReader::new(&b"superuser\n"[..]);
+        assert!(parse_level(&mut buffer).is_err());
+    }
+
+    #[test]
+    fn parse_prepare_responses() {
+        let mut buffer = BufReader::new(&b"New version created: 5\n\n"[..]);
+        assert_eq!(parse_prepare(&mut buffer).unwrap(), 5);
+
+        let mut buffer =
+            BufReader::new(&b"Unknown ACL identifier. Please use #<id> or <file>.\n\n"[..]);
+        assert!(matches!(parse_prepare(&mut buffer), Err(Error::UnknownId)));
+
+        let mut buffer = BufReader::new(&b"\n"[..]);
+        assert!(matches!(
+            parse_prepare(&mut buffer),
+            Err(Error::ParseFailure)
+        ));
+    }
+
+    #[test]
+    fn parse_confirmation_responses() {
+        let mut buffer = BufReader::new(&b"Server deleted.\n\n"[..]);
+        assert!(parse_confirmation(&mut buffer, "Server deleted.").is_ok());
+
+        let mut buffer =
+            BufReader::new(&b"Only servers in maintenance mode can be deleted.\n\n"[..]);
+        assert!(matches!(
+            parse_confirmation(&mut buffer, "Server deleted."),
+            Err(Error::InvalidState)
+        ));
+
+        let mut buffer = BufReader::new(
+            &b"Server still has connections attached to it, cannot remove it.\n\n"[..],
+        );
+        assert!(matches!(
+            parse_confirmation(&mut buffer, "Server deleted."),
+            Err(Error::InvalidState)
+        ));
+
+        let mut buffer = BufReader::new(&b"\n"[..]);
+        assert!(matches!(
+            parse_confirmation(&mut buffer, "Server deleted."),
+            Err(Error::ParseFailure)
+        ));
+    }
+
+    #[test]
+    fn parse_servers_state_valid_input() {
+        let mut buffer = BufReader::new(
+            &b"1
+# be_id be_name srv_id srv_name srv_addr srv_op_state srv_admin_state srv_uweight srv_iweight srv_time_since_last_change srv_check_status srv_check_result srv_check_health srv_check_state srv_agent_state bk_f_forced_id srv_f_forced_id srv_fqdn srv_port srvrecord srv_use_ssl srv_check_port srv_check_addr srv_agent_addr srv_agent_port
+3 servers 1 server1 127.0.0.1 2 0 1 1 51 1 0 0 0 0 0 0 - 8000 - 0 0 - - 0
+3 servers 2 server2 127.0.0.2 0 1 1 1 51 1 0 0 0 0 0 0 - 8001 - 0 0 - - 0
+
+"[..],
+        );
+        let rows = parse_servers_state(&mut buffer).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].srv_name, "server1");
+        assert_eq!(rows[0].srv_port, Some(8000));
+        assert_eq!(
+            rows[1].srv_op_state,
+            Some(responses::ServerOpState::Stopped)
+        );
+        assert!(rows[1].srv_admin_state.unwrap().is_maint());
+    }
+
+    #[test]
+    fn parse_backends_valid_input() {
+        let mut buffer = BufReader::new(&b"# name\nstats\nservers\n\n"[..]);
+        assert_eq!(
+            parse_backends(&mut buffer).unwrap(),
+            vec!["stats", "servers"]
+        );
+    }
+
+    #[test]
+    fn parse_backends_error() {
+        let mut buffer = BufReader::new(&b"Permission denied.\n\n"[..]);
+        assert!(matches!(
+            parse_backends(&mut buffer),
+            Err(Error::CommandFailed(message)) if message == "Permission denied."
+        ));
+    }
+
+    #[test]
+    fn parse_servers_conn_valid_input() {
+        let mut buffer = BufReader::new(
+            &b"# bkname/svname bkid/svid addr port - purge_delay used_cur used_max need_est unsafe_nb safe_nb idle_lim idle_cur idle_per_thr[2]
+servers/server1 3/1 127.0.0.1 8000 - 5000 0 1 0 0 0 -1 2 1 1
+
+"[..],
+        );
+        let rows = parse_servers_conn(&mut buffer).unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].srv_name, "server1");
+        assert_eq!(rows[0].used_max, Some(1));
+        assert_eq!(rows[0].idle_cur, Some(2));
+        assert_eq!(rows[0].idle_per_thread, vec![1, 1]);
+
+        let mut buffer = BufReader::new(&b"Can't find backend.\n\n"[..]);
+        assert!(matches!(
+            parse_servers_conn(&mut buffer),
+            Err(Error::UnknownId)
+        ));
+    }
+
+    #[test]
+    fn parse_servers_state_errors() {
+        let mut buffer = BufReader::new(&b"Can't find backend.\n\n"[..]);
+        assert!(matches!(
+            parse_servers_state(&mut buffer),
+            Err(Error::UnknownId)
+        ));
+
+        let mut buffer = BufReader::new(&b"2\n# be_id be_name\n"[..]);
+        assert!(matches!(
+            parse_servers_state(&mut buffer),
+            Err(Error::ParseFailure)
+        ));
+    }
+
+    #[test]
+    fn parse_sessions_valid_input() {
+        let mut buffer = BufReader::new(
+            &b"0x55d1c3e6a5f0: proto=tcpv4 src=127.0.0.1:54321 fe=http be=servers srv=server1 ts=00 age=3s calls=2 rq[f=848000h] rp[f=80048000h] s0=[7,8h,fd=13,ex=] s1=[7,118h,fd=-1,ex=] exp=
+0x55d1c3e6b000: proto=unix_stream src=unix:1 fe=GLOBAL be=<NONE> srv=<none> ts=00 age=0s calls=1 rq[f=c08200h] rp[f=80008002h] s0=[7,8h,fd=14,ex=] s1=[7,4018h,fd=-1,ex=] exp=
+
+"[..],
+        );
+        let sessions = parse_sessions(&mut buffer).unwrap();
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].be.as_deref(), Some("servers"));
+        assert_eq!(sessions[1].fe.as_deref(), Some("GLOBAL"));
+    }
+
+    #[test]
+    fn parse_session_detail_responses() {
+        let mut buffer = BufReader::new(
+            &b"0x55d1c3e6a5f0: [16/Oct/2026:10:00:00.123456] id=5 proto=tcpv4 source=127.0.0.1:54321
+  flags=0x4ce, conn_retries=0, srv_conn=0x55d1c3e70000, pend_pos=(nil) waiting=0
+
+"[..],
+        );
+        let detail = parse_session_detail(&mut buffer).unwrap();
+        assert!(detail.starts_with("0x55d1c3e6a5f0: "));
+        assert!(detail.ends_with("waiting=0"));
+
+        let mut buffer = BufReader::new(&b"Session not found.\n\n"[..]);
+        assert!(matches!(
+            parse_session_detail(&mut buffer),
+            Err(Error::NotFound)
+        ));
+    }
+
+    #[test]
+    fn parse_state_change_responses() {
+        let unchanged = "All sockets are already disabled.";
+
+        let mut buffer = BufReader::new(&b"\n"[..]);
+        assert_eq!(
+            parse_state_change(&mut buffer, unchanged).unwrap(),
+            StateChange::Applied
+        );
+
+        let mut buffer = BufReader::new(&b"All sockets are already disabled.\n\n"[..]);
+        assert_eq!(
+            parse_state_change(&mut buffer, unchanged).unwrap(),
+            StateChange::Unchanged
+        );
+
+        let mut buffer =
+            BufReader::new(&b"Frontend was previously shut down, cannot disable.\n\n"[..]);
+        assert!(matches!(
+            parse_state_change(&mut buffer, unchanged),
+            Err(Error::InvalidState)
+        ));
+
+        let mut buffer = BufReader::new(&b"No such frontend.\n\n"[..]);
+        assert!(matches!(
+            parse_state_change(&mut buffer, unchanged),
+            Err(Error::UnknownId)
+        ));
+
+        let mut buffer = BufReader::new(&b""[..]);
+        assert!(matches!(
+            parse_state_change(&mut buffer, unchanged),
+            Err(Error::ParseFailure)
+        ));
+    }
+
+    #[test]
+    fn parse_tables_valid_input() {
+        let mut buffer = BufReader::new(
+            &b"# table: http-in, type: ip, size:1048576, used:2\n# table: keys, type: string, size:100, used:0\n\n"[..],
+        );
+        let tables = parse_tables(&mut buffer).unwrap();
+
+        assert_eq!(tables.len(), 2);
+        assert_eq!(tables[0].name, "http-in");
+        assert_eq!(tables[1].table_type, models::StickTableType::String);
+    }
+
+    #[test]
+    fn parse_table_entries_valid_input() {
+        let mut buffer = BufReader::new(
+            &b"# table: http-in, type: ip, size:1048576, used:2
+0x55d1c3e6a5f0: key=127.0.0.1 use=0 exp=86399000 gpc0=1 conn_rate(10000)=3
+0x55d1c3e6b000: key=127.0.0.2 use=1 exp=86000000 gpc0=0 conn_rate(10000)=0
+
+"[..],
+        );
+        let entries = parse_table_entries(&mut buffer).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].use_count, 1);
+        assert_eq!(
+            entries[0].data["gpc0"],
+            responses::StickTableData::Unsigned(1)
+        );
+    }
+
+    #[test]
+    fn parse_table_entries_errors() {
+        let mut buffer = BufReader::new(&b"No such table\n\n"[..]);
+        assert!(matches!(
+            parse_table_entries(&mut buffer),
+            Err(Error::UnknownId)
+        ));
+
+        let mut buffer = BufReader::new(&b"Data type not stored in this table\n\n"[..]);
+        assert!(matches!(
+            parse_table_entries(&mut buffer),
+            Err(Error::CommandFailed(_))
+        ));
+    }
+
+    #[test]
+    fn parse_empty_response_responses() {
+        let mut buffer = BufReader::new(&b"\n"[..]);
+        assert!(parse_empty_response(&mut buffer).is_ok());
+
+        let mut buffer = BufReader::new(
+            &b"'add map' expects three parameters: map identifier, key and value.\n\n"[..],
+        );
+        assert!(matches!(
+            parse_empty_response(&mut buffer),
+            Err(Error::MissingParameters)
+        ));
+
+        let mut buffer =
+            BufReader::new(&b"Unknown map identifier. Please use #<id> or <file>.\n\n"[..]);
+        assert!(matches!(
+            parse_empty_response(&mut buffer),
+            Err(Error::UnknownId)
+        ));
+
+        let mut buffer = BufReader::new(&b"No such server.\n\n"[..]);
+        assert!(matches!(
+            parse_empty_response(&mut buffer),
+            Err(Error::UnknownId)
+        ));
+
+        let mut buffer = BufReader::new(&b"Value out of range.\n\n"[..]);
+        assert!(matches!(
+            parse_empty_response(&mut buffer),
+            Err(Error::InvalidArgument)
+        ));
+
+        let mut buffer = BufReader::new(&b"Key not found.\n\n"[..]);
+        assert!(matches!(
+            parse_empty_response(&mut buffer),
+            Err(Error::NotFound)
+        ));
+
+        let mut buffer = BufReader::new(&b"Permission denied\n\n"[..]);
+        match parse_empty_response(&mut buffer) {
+            Err(Error::CommandFailed(message)) => assert_eq!(message, "Permission denied"),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
 }
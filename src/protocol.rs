@@ -0,0 +1,1091 @@
+//! Transport-independent encoding of commands and parsing of responses.
+//!
+//! Each function in this module validates its arguments and returns a [`Request`], which
+//! implements [`Command`]. Each argument is escaped so that spaces, `;` and `\` are passed to
+//! HAProxy as written; arguments containing a newline are rejected with `Error::InvalidArgument`.
+//! A command serializes to the bytes to send to HAProxy and creates a [`Response`], which is fed
+//! HAProxy's output as it arrives and parsed once the response is complete.
+//! [`Connection`](crate::Connection) drives commands over blocking sockets, but any transport,
+//! such as an event loop, can do the same.
+//!
+//! In HAProxy's default mode, the response is complete once HAProxy closes the connection. In
+//! interactive prompt mode, it is complete at the next `> ` prompt: create the parser with
+//! [`Command::prompt_response`] and [`Response::feed`] reports when the prompt arrives.
+//! [`PromptScanner`] finds the prompt in raw output for transports which parse responses
+//! themselves.
+//!
+//! # Examples
+//! ```
+//! use haptik::models::AclId;
+//! use haptik::protocol::{self, Command, Response};
+//!
+//! let request = protocol::acl_get(AclId::Id(0), "127.0.0.1").expect("Invalid value");
+//! assert_eq!(request.to_bytes(), b"get acl #0 127.0.0.1\n");
+//!
+//! let mut response = request.response();
+//! response.feed(b"type=ip, case=sensitive, match=yes, ");
+//! response.feed(b"idx=tree, pattern=\"127.0.0.1\"\n\n");
+//! let acl_match = response.finish().expect("Failed to parse response");
+//! assert!(acl_match.matched);
+//! ```
+//!
+//! In prompt mode, `feed` returns how much of the output belongs to the response once the prompt
+//! is found.
+//! ```
+//! use haptik::models::AclId;
+//! use haptik::protocol::{self, Command, Progress, Response};
+//!
+//! let request = protocol::acl_get(AclId::Id(0), "127.0.0.1").expect("Invalid value");
+//! let mut response = request.prompt_response();
+//! let output = b"type=ip, case=sensitive, match=no\n\n> ";
+//! assert_eq!(response.feed(output), Progress::Complete(output.len()));
+//! assert!(!response.finish().expect("Failed to parse response").matched);
+//! ```
+
+use std::io::{self, BufReader, Cursor};
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use crate::commands;
+use crate::errors::Error;
+use crate::models;
+use crate::parsers;
+use crate::requests::{
+    AgentState, BackendId, EntryRef, ErrorFlag, HealthState, RateLimit, ServerId, ServerSpec,
+    ServerState, StatFilter, TableFilter, Weight,
+};
+use crate::responses;
+
+pub(crate) type Parser<R> = fn(&mut BufReader<Cursor<Vec<u8>>>) -> Result<R, Error>;
+
+/// A command which can be sent to HAProxy.
+pub trait Command {
+    type Response: Response;
+
+    /// Serialize the command, including the trailing newline.
+    fn to_bytes(&self) -> Vec<u8>;
+
+    /// Create a parser for the response to this command, which ends when HAProxy closes the
+    /// connection.
+    fn response(&self) -> Self::Response;
+
+    /// Create a parser for the response to this command in interactive prompt mode, which ends at
+    /// the next `> ` prompt.
+    fn prompt_response(&self) -> Self::Response;
+}
+
+/// A parser for HAProxy's response to a [`Command`].
+pub trait Response {
+    type Output;
+
+    /// Add the next chunk of HAProxy's output.
+    ///
+    /// Returns [`Progress::Complete`] once the end of the response has been seen. Only responses
+    /// in prompt mode can tell where they end; otherwise the response is complete when HAProxy
+    /// closes the connection, and this always returns [`Progress::Incomplete`].
+    fn feed(&mut self, data: &[u8]) -> Progress;
+
+    /// Parse the complete response.
+    fn finish(self) -> Result<Self::Output, Error>;
+}
+
+/// Whether a response has been received in full.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Progress {
+    /// All of the data belongs to the response, and more is needed.
+    Incomplete,
+    /// The response is complete. Holds the number of bytes of the data which belong to it,
+    /// including the prompt; the rest was not consumed.
+    Complete(usize),
+}
+
+/// Finds the `> ` prompt which ends each response in interactive prompt mode.
+///
+/// The prompt is only recognized at the start of the response or of a line, so responses
+/// containing a newline followed by `> ` cannot be delimited reliably.
+///
+/// # Examples
+/// ```
+/// use haptik::protocol::{Progress, PromptScanner};
+///
+/// let mut scanner = PromptScanner::default();
+/// assert_eq!(scanner.scan(b"Done.\n"), Progress::Incomplete);
+/// assert_eq!(scanner.scan(b"\n> next"), Progress::Complete(3));
+/// assert!(scanner.is_complete());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PromptScanner {
+    state: PromptState,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+enum PromptState {
+    /// At the start of a line, where the prompt may begin.
+    #[default]
+    LineStart,
+    /// After a `>` at the start of a line.
+    Marker,
+    /// Within a line which is not the prompt.
+    Line,
+    /// The prompt has been found.
+    Found,
+}
+
+impl PromptScanner {
+    /// Scan the next chunk of output for the prompt.
+    ///
+    /// Once the prompt has been found, further output belongs to the next response and this
+    /// returns `Progress::Complete(0)`.
+    pub fn scan(&mut self, data: &[u8]) -> Progress {
+        if self.state == PromptState::Found {
+            return Progress::Complete(0);
+        }
+
+        for (i, byte) in data.iter().enumerate() {
+            self.state = match (self.state, byte) {
+                (PromptState::Marker, b' ') => {
+                    self.state = PromptState::Found;
+                    return Progress::Complete(i + 1);
+                }
+                (_, b'\n') => PromptState::LineStart,
+                (PromptState::LineStart, b'>') => PromptState::Marker,
+                _ => PromptState::Line,
+            };
+        }
+
+        Progress::Incomplete
+    }
+
+    /// Whether the prompt has been found.
+    pub fn is_complete(&self) -> bool {
+        self.state == PromptState::Found
+    }
+}
+
+/// A command to HAProxy whose response parses into `R`.
+#[derive(Clone, Debug)]
+pub struct Request<R> {
+    command: Vec<u8>,
+    parse: Parser<R>,
+}
+
+impl<R> Request<R> {
+    /// Encode a command whose arguments have been escaped as they were written.
+    ///
+    /// A newline ends the command and cannot be escaped, so commands containing one are rejected
+    /// with `Error::InvalidArgument`.
+    fn new<F>(write: F, parse: Parser<R>) -> Result<Self, Error>
+    where
+        F: FnOnce(&mut Vec<u8>) -> io::Result<()>,
+    {
+        let mut command = Vec::new();
+        write(&mut command).expect("Writing to a Vec cannot fail");
+
+        if command.contains(&b'\n') {
+            return Err(Error::InvalidArgument);
+        }
+
+        Ok(Self { command, parse })
+    }
+
+    /// Encode a command which takes no text from the caller, and so is always valid.
+    fn fixed<F>(write: F, parse: Parser<R>) -> Self
+    where
+        F: FnOnce(&mut Vec<u8>) -> io::Result<()>,
+    {
+        Self::new(write, parse).expect("Commands without text arguments are always valid")
+    }
+
+    /// The escaped command without the trailing newline.
+    pub(crate) fn command(&self) -> &[u8] {
+        &self.command
+    }
+
+    pub(crate) fn parser(&self) -> Parser<R> {
+        self.parse
+    }
+}
+
+impl<R> Command for Request<R> {
+    type Response = ResponseParser<R>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.command.clone();
+        commands::end(&mut bytes).expect("Writing to a Vec cannot fail");
+        bytes
+    }
+
+    fn response(&self) -> Self::Response {
+        ResponseParser {
+            buffer: Vec::new(),
+            prompt: None,
+            parse: self.parse,
+        }
+    }
+
+    fn prompt_response(&self) -> Self::Response {
+        ResponseParser {
+            buffer: Vec::new(),
+            prompt: Some(PromptScanner::default()),
+            parse: self.parse,
+        }
+    }
+}
+
+/// Collects the response to a [`Request`] and parses it once complete.
+#[derive(Clone, Debug)]
+pub struct ResponseParser<R> {
+    buffer: Vec<u8>,
+    /// Finds the end of the response in prompt mode.
+    prompt: Option<PromptScanner>,
+    parse: Parser<R>,
+}
+
+impl<R> Response for ResponseParser<R> {
+    type Output = R;
+
+    fn feed(&mut self, data: &[u8]) -> Progress {
+        let progress = match &mut self.prompt {
+            Some(prompt) => prompt.scan(data),
+            None => Progress::Incomplete,
+        };
+
+        match progress {
+            Progress::Incomplete => self.buffer.extend_from_slice(data),
+            Progress::Complete(len) => self.buffer.extend_from_slice(&data[..len]),
+        }
+        progress
+    }
+
+    /// Parse the complete response.
+    ///
+    /// In prompt mode, fails with an `UnexpectedEof` I/O error if the prompt was never found.
+    fn finish(mut self) -> Result<R, Error> {
+        if let Some(prompt) = &self.prompt {
+            if !prompt.is_complete() {
+                return Err(Error::IoError(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "connection closed before the prompt",
+                )));
+            }
+            self.buffer.truncate(self.buffer.len() - 2);
+        }
+
+        (self.parse)(&mut BufReader::new(Cursor::new(self.buffer)))
+    }
+}
+
+/// The command issued by [`Connection::acl_add`](crate::Connection::acl_add).
+pub fn acl_add<E: ToString>(id: models::AclId, value: E) -> Result<Request<()>, Error> {
+    Request::new(
+        |w| commands::add_acl(w, None, id, &value.to_string()),
+        parsers::parse_acl_add,
+    )
+}
+
+/// The command issued by [`Connection::acl_add_version`](crate::Connection::acl_add_version).
+pub fn acl_add_version<E: ToString>(
+    id: models::AclId,
+    version: u32,
+    value: E,
+) -> Result<Request<()>, Error> {
+    Request::new(
+        |w| commands::add_acl(w, Some(version), id, &value.to_string()),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::acl_clear`](crate::Connection::acl_clear).
+pub fn acl_clear(id: models::AclId) -> Result<Request<()>, Error> {
+    Request::new(
+        |w| commands::clear_acl(w, None, id),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::acl_clear_version`](crate::Connection::acl_clear_version).
+pub fn acl_clear_version(id: models::AclId, version: u32) -> Result<Request<()>, Error> {
+    Request::new(
+        |w| commands::clear_acl(w, Some(version), id),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::acl_commit`](crate::Connection::acl_commit).
+pub fn acl_commit(id: models::AclId, version: u32) -> Result<Request<()>, Error> {
+    Request::new(
+        |w| commands::commit_acl(w, version, id),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::acl_data`](crate::Connection::acl_data).
+pub fn acl_data<E: FromStr>(id: models::AclId) -> Result<Request<Vec<models::AclEntry<E>>>, Error> {
+    Request::new(
+        |w| commands::show_acl_entries(w, id),
+        parsers::parse_acl_entries,
+    )
+}
+
+/// The command issued by [`Connection::acl_del`](crate::Connection::acl_del).
+pub fn acl_del(id: models::AclId, entry: EntryRef) -> Result<Request<()>, Error> {
+    Request::new(
+        |w| commands::del_acl(w, id, entry),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::acl_get`](crate::Connection::acl_get).
+pub fn acl_get<E: ToString>(
+    id: models::AclId,
+    value: E,
+) -> Result<Request<responses::AclMatch>, Error> {
+    Request::new(
+        |w| commands::get_acl(w, id, &value.to_string()),
+        parsers::parse_match,
+    )
+}
+
+/// The command issued by [`Connection::acl_list`](crate::Connection::acl_list).
+pub fn acl_list() -> Request<Vec<responses::Acl>> {
+    Request::fixed(commands::show_acl, parsers::parse_acl_list)
+}
+
+/// The command issued by [`Connection::acl_prepare`](crate::Connection::acl_prepare).
+pub fn acl_prepare(id: models::AclId) -> Result<Request<u32>, Error> {
+    Request::new(|w| commands::prepare_acl(w, id), parsers::parse_prepare)
+}
+
+/// The command issued by [`Connection::map_add`](crate::Connection::map_add).
+pub fn map_add<K: ToString, V: ToString>(
+    id: models::MapId,
+    key: K,
+    value: V,
+) -> Result<Request<()>, Error> {
+    Request::new(
+        |w| commands::add_map(w, None, id, &key.to_string(), &value.to_string()),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::map_add_version`](crate::Connection::map_add_version).
+pub fn map_add_version<K: ToString, V: ToString>(
+    id: models::MapId,
+    version: u32,
+    key: K,
+    value: V,
+) -> Result<Request<()>, Error> {
+    Request::new(
+        |w| commands::add_map(w, Some(version), id, &key.to_string(), &value.to_string()),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::map_clear`](crate::Connection::map_clear).
+pub fn map_clear(id: models::MapId) -> Result<Request<()>, Error> {
+    Request::new(
+        |w| commands::clear_map(w, None, id),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::map_clear_version`](crate::Connection::map_clear_version).
+pub fn map_clear_version(id: models::MapId, version: u32) -> Result<Request<()>, Error> {
+    Request::new(
+        |w| commands::clear_map(w, Some(version), id),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::map_commit`](crate::Connection::map_commit).
+pub fn map_commit(id: models::MapId, version: u32) -> Result<Request<()>, Error> {
+    Request::new(
+        |w| commands::commit_map(w, version, id),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::map_entries`](crate::Connection::map_entries).
+pub fn map_entries<K: FromStr, V: FromStr>(
+    id: models::MapId,
+) -> Result<Request<Vec<models::MapEntry<K, V>>>, Error> {
+    Request::new(
+        |w| commands::show_map_entries(w, id),
+        parsers::parse_map_entries,
+    )
+}
+
+/// The command issued by [`Connection::map_del`](crate::Connection::map_del).
+pub fn map_del(id: models::MapId, entry: EntryRef) -> Result<Request<()>, Error> {
+    Request::new(
+        |w| commands::del_map(w, id, entry),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::map_get`](crate::Connection::map_get).
+pub fn map_get<K: ToString>(
+    id: models::MapId,
+    key: K,
+) -> Result<Request<responses::MapMatch>, Error> {
+    Request::new(
+        |w| commands::get_map(w, id, &key.to_string()),
+        parsers::parse_match,
+    )
+}
+
+/// The command issued by [`Connection::map_list`](crate::Connection::map_list).
+pub fn map_list() -> Request<Vec<responses::Map>> {
+    Request::fixed(commands::show_map, parsers::parse_map_list)
+}
+
+/// The command issued by [`Connection::map_set`](crate::Connection::map_set).
+pub fn map_set<V: ToString>(
+    id: models::MapId,
+    entry: EntryRef,
+    value: V,
+) -> Result<Request<()>, Error> {
+    Request::new(
+        |w| commands::set_map(w, id, entry, &value.to_string()),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::map_prepare`](crate::Connection::map_prepare).
+pub fn map_prepare(id: models::MapId) -> Result<Request<u32>, Error> {
+    Request::new(|w| commands::prepare_map(w, id), parsers::parse_prepare)
+}
+
+/// The command issued by [`Connection::level`](crate::Connection::level).
+pub fn level() -> Request<responses::Level> {
+    Request::fixed(commands::show_cli_level, parsers::parse_level)
+}
+
+/// The command issued by [`Connection::backends`](crate::Connection::backends).
+pub fn backends() -> Request<Vec<String>> {
+    Request::fixed(commands::show_backend, parsers::parse_backends)
+}
+
+/// The command issued by [`Connection::cli_sockets`](crate::Connection::cli_sockets).
+pub fn cli_sockets() -> Request<Vec<responses::CliSocket>> {
+    Request::fixed(commands::show_cli_sockets, parsers::parse_cli_sockets)
+}
+
+/// The command issued by [`Connection::errors`](crate::Connection::errors).
+pub fn errors() -> Request<u32> {
+    Request::fixed(commands::show_errors, parsers::parse_errors)
+}
+
+/// The command issued by [`Connection::errors_backend`](crate::Connection::errors_backend).
+pub fn errors_backend(backend: BackendId, error_type: ErrorFlag) -> Result<Request<u32>, Error> {
+    Request::new(
+        |w| commands::show_errors_backend(w, backend, error_type),
+        parsers::parse_errors,
+    )
+}
+
+/// The command issued by [`Connection::add_server`](crate::Connection::add_server).
+pub fn add_server(backend: BackendId, name: &str, spec: &ServerSpec) -> Result<Request<()>, Error> {
+    check_server_backend(&backend)?;
+    if name.is_empty() || !spec.is_valid() {
+        return Err(Error::InvalidArgument);
+    }
+
+    Request::new(
+        |w| commands::add_server(w, backend, name, spec),
+        |r| parsers::parse_confirmation(r, "New server registered."),
+    )
+}
+
+/// The command issued by [`Connection::del_server`](crate::Connection::del_server).
+pub fn del_server(backend: BackendId, server: ServerId) -> Result<Request<()>, Error> {
+    check_server_backend(&backend)?;
+
+    Request::new(
+        |w| commands::del_server(w, backend, server),
+        |r| parsers::parse_confirmation(r, "Server deleted."),
+    )
+}
+
+/// The command issued by [`Connection::disable_frontend`](crate::Connection::disable_frontend).
+pub fn disable_frontend(frontend: &str) -> Result<Request<responses::StateChange>, Error> {
+    Request::new(
+        |w| commands::disable_frontend(w, frontend),
+        |r| parsers::parse_state_change(r, "All sockets are already disabled."),
+    )
+}
+
+/// The command issued by [`Connection::disable_health`](crate::Connection::disable_health).
+pub fn disable_health(backend: BackendId, server: ServerId) -> Result<Request<()>, Error> {
+    check_server_backend(&backend)?;
+
+    Request::new(
+        |w| commands::disable_health(w, backend, server),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::disable_server`](crate::Connection::disable_server).
+pub fn disable_server(backend: BackendId, server: ServerId) -> Result<Request<()>, Error> {
+    check_server_backend(&backend)?;
+
+    Request::new(
+        |w| commands::disable_server(w, backend, server),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::enable_frontend`](crate::Connection::enable_frontend).
+pub fn enable_frontend(frontend: &str) -> Result<Request<responses::StateChange>, Error> {
+    Request::new(
+        |w| commands::enable_frontend(w, frontend),
+        |r| parsers::parse_state_change(r, "All sockets are already enabled."),
+    )
+}
+
+/// The command issued by [`Connection::enable_health`](crate::Connection::enable_health).
+pub fn enable_health(backend: BackendId, server: ServerId) -> Result<Request<()>, Error> {
+    check_server_backend(&backend)?;
+
+    Request::new(
+        |w| commands::enable_health(w, backend, server),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::enable_server`](crate::Connection::enable_server).
+pub fn enable_server(backend: BackendId, server: ServerId) -> Result<Request<()>, Error> {
+    check_server_backend(&backend)?;
+
+    Request::new(
+        |w| commands::enable_server(w, backend, server),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::info`](crate::Connection::info).
+pub fn info() -> Request<responses::Info> {
+    Request::fixed(commands::show_info, parsers::parse_info)
+}
+
+/// The command issued by [`Connection::info_typed`](crate::Connection::info_typed).
+pub fn info_typed() -> Request<Vec<responses::TypedInfo>> {
+    Request::fixed(commands::show_info_typed, parsers::parse_info_typed)
+}
+
+/// The command issued by [`Connection::info_json`](crate::Connection::info_json).
+#[cfg(feature = "json")]
+pub fn info_json() -> Request<responses::Tagged<responses::Info>> {
+    Request::fixed(commands::show_info_json, parsers::parse_info_json)
+}
+
+/// The command issued by [`Connection::schema_json`](crate::Connection::schema_json).
+#[cfg(feature = "json")]
+pub fn schema_json() -> Request<serde_json::Value> {
+    Request::fixed(commands::show_schema_json, parsers::parse_schema_json)
+}
+
+/// The command issued by [`Connection::servers_conn`](crate::Connection::servers_conn).
+pub fn servers_conn(
+    backend: Option<BackendId>,
+) -> Result<Request<Vec<responses::ServerConnections>>, Error> {
+    let backend = backend_name(backend)?;
+
+    Request::new(
+        |w| commands::show_servers_conn(w, backend),
+        parsers::parse_servers_conn,
+    )
+}
+
+/// The command issued by [`Connection::servers_state`](crate::Connection::servers_state).
+pub fn servers_state(
+    backend: Option<BackendId>,
+) -> Result<Request<Vec<responses::ServerStateRow>>, Error> {
+    let backend = backend_name(backend)?;
+
+    Request::new(
+        |w| commands::show_servers_state(w, backend),
+        parsers::parse_servers_state,
+    )
+}
+
+/// The command issued by [`Connection::sessions`](crate::Connection::sessions).
+pub fn sessions() -> Request<Vec<responses::Session>> {
+    Request::fixed(commands::show_sess, parsers::parse_sessions)
+}
+
+/// The command issued by [`Connection::session`](crate::Connection::session).
+pub fn session(ptr: u64) -> Request<String> {
+    Request::fixed(
+        |w| commands::show_sess_detail(w, ptr),
+        parsers::parse_session_detail,
+    )
+}
+
+/// The command issued by [`Connection::set_maxconn_frontend`](crate::Connection::set_maxconn_frontend).
+pub fn set_maxconn_frontend(frontend: &str, maxconn: u32) -> Result<Request<()>, Error> {
+    check_limit(maxconn)?;
+
+    Request::new(
+        |w| commands::set_maxconn_frontend(w, frontend, maxconn),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::set_maxconn_global`](crate::Connection::set_maxconn_global).
+pub fn set_maxconn_global(maxconn: u32) -> Result<Request<()>, Error> {
+    check_limit(maxconn)?;
+
+    Request::new(
+        |w| commands::set_maxconn_global(w, maxconn),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::set_maxconn_server`](crate::Connection::set_maxconn_server).
+pub fn set_maxconn_server(
+    backend: BackendId,
+    server: ServerId,
+    maxconn: u32,
+) -> Result<Request<()>, Error> {
+    check_server_backend(&backend)?;
+    check_limit(maxconn)?;
+
+    Request::new(
+        |w| commands::set_maxconn_server(w, backend, server, maxconn),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::set_rate_limit`](crate::Connection::set_rate_limit).
+pub fn set_rate_limit(limit: RateLimit, value: u32) -> Result<Request<()>, Error> {
+    check_limit(value)?;
+
+    Request::new(
+        |w| commands::set_rate_limit(w, limit, value),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::set_server_addr`](crate::Connection::set_server_addr).
+pub fn set_server_addr(
+    backend: BackendId,
+    server: ServerId,
+    addr: IpAddr,
+    port: Option<u16>,
+) -> Result<Request<responses::AddrChange>, Error> {
+    check_server_backend(&backend)?;
+
+    Request::new(
+        |w| commands::set_server_addr(w, backend, server, addr, port),
+        parsers::parse_addr_change,
+    )
+}
+
+/// The command issued by [`Connection::set_server_agent`](crate::Connection::set_server_agent).
+pub fn set_server_agent(
+    backend: BackendId,
+    server: ServerId,
+    state: AgentState,
+) -> Result<Request<()>, Error> {
+    check_server_backend(&backend)?;
+
+    Request::new(
+        |w| commands::set_server_agent(w, backend, server, state),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::set_server_health`](crate::Connection::set_server_health).
+pub fn set_server_health(
+    backend: BackendId,
+    server: ServerId,
+    state: HealthState,
+) -> Result<Request<()>, Error> {
+    check_server_backend(&backend)?;
+
+    Request::new(
+        |w| commands::set_server_health(w, backend, server, state),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::set_server_state`](crate::Connection::set_server_state).
+pub fn set_server_state(
+    backend: BackendId,
+    server: ServerId,
+    state: ServerState,
+) -> Result<Request<()>, Error> {
+    check_server_backend(&backend)?;
+
+    Request::new(
+        |w| commands::set_server_state(w, backend, server, state),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::set_server_weight`](crate::Connection::set_server_weight).
+pub fn set_server_weight(
+    backend: BackendId,
+    server: ServerId,
+    weight: Weight,
+) -> Result<Request<()>, Error> {
+    check_server_backend(&backend)?;
+    if let Weight::Absolute(weight) = weight {
+        if weight > Weight::MAX {
+            return Err(Error::InvalidArgument);
+        }
+    }
+
+    Request::new(
+        |w| commands::set_server_weight(w, backend, server, weight),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::shutdown_frontend`](crate::Connection::shutdown_frontend).
+pub fn shutdown_frontend(frontend: &str) -> Result<Request<responses::StateChange>, Error> {
+    Request::new(
+        |w| commands::shutdown_frontend(w, frontend),
+        |r| parsers::parse_state_change(r, "Frontend was already shut down."),
+    )
+}
+
+/// The command issued by [`Connection::table_clear`](crate::Connection::table_clear).
+pub fn table_clear(table: &str, filter: Option<&TableFilter>) -> Result<Request<()>, Error> {
+    if let Some(filter) = filter {
+        check_table_filter(filter)?;
+    }
+
+    Request::new(
+        |w| commands::clear_table(w, table, filter),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::tables`](crate::Connection::tables).
+pub fn tables() -> Request<Vec<responses::StickTable>> {
+    Request::fixed(commands::show_table, parsers::parse_tables)
+}
+
+/// The command issued by [`Connection::table_entries`](crate::Connection::table_entries).
+pub fn table_entries(
+    table: &str,
+    filter: Option<&TableFilter>,
+) -> Result<Request<Vec<responses::StickTableEntry>>, Error> {
+    if let Some(filter) = filter {
+        check_table_filter(filter)?;
+    }
+
+    Request::new(
+        |w| commands::show_table_entries(w, table, filter),
+        parsers::parse_table_entries,
+    )
+}
+
+/// The command issued by [`Connection::table_set`](crate::Connection::table_set).
+pub fn table_set(
+    table: &str,
+    key: &models::StickTableKey,
+    data: &[(&str, u64)],
+) -> Result<Request<()>, Error> {
+    check_table_key(key)?;
+    if data.iter().any(|(data_type, _)| data_type.is_empty()) {
+        return Err(Error::InvalidArgument);
+    }
+
+    Request::new(
+        |w| commands::set_table(w, table, key, data),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::shutdown_session`](crate::Connection::shutdown_session).
+pub fn shutdown_session(ptr: u64) -> Request<()> {
+    Request::fixed(
+        |w| commands::shutdown_session(w, ptr),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by
+/// [`Connection::shutdown_sessions_server`](crate::Connection::shutdown_sessions_server).
+pub fn shutdown_sessions_server(
+    backend: BackendId,
+    server: ServerId,
+) -> Result<Request<()>, Error> {
+    check_server_backend(&backend)?;
+
+    Request::new(
+        |w| commands::shutdown_sessions_server(w, backend, server),
+        parsers::parse_empty_response,
+    )
+}
+
+/// The command issued by [`Connection::stats`](crate::Connection::stats).
+pub fn stats() -> Request<Vec<responses::StatRow>> {
+    Request::fixed(commands::show_stat, parsers::parse_stats)
+}
+
+/// The command issued by [`Connection::stats_typed`](crate::Connection::stats_typed).
+pub fn stats_typed() -> Request<Vec<responses::TypedStat>> {
+    Request::fixed(commands::show_stat_typed, parsers::parse_stats_typed)
+}
+
+/// The command issued by [`Connection::stats_json`](crate::Connection::stats_json).
+#[cfg(feature = "json")]
+pub fn stats_json() -> Request<Vec<responses::Tagged<responses::StatRow>>> {
+    Request::fixed(commands::show_stat_json, parsers::parse_stats_json)
+}
+
+/// The command issued by [`Connection::stats_filtered`](crate::Connection::stats_filtered).
+pub fn stats_filtered(filter: StatFilter) -> Result<Request<Vec<responses::StatRow>>, Error> {
+    Request::new(
+        |w| commands::show_stat_filtered(w, filter),
+        parsers::parse_stats,
+    )
+}
+
+/// Get the backend name for commands which only look up backends by name.
+fn backend_name<'a>(backend: Option<BackendId<'a>>) -> Result<Option<&'a str>, Error> {
+    match backend {
+        None | Some(BackendId::All) => Ok(None),
+        Some(BackendId::Name(name)) => Ok(Some(name)),
+        Some(BackendId::Id(_)) => Err(Error::InvalidArgument),
+    }
+}
+
+/// HAProxy stores limits as signed integers, so larger values are rejected before sending them.
+fn check_limit(value: u32) -> Result<(), Error> {
+    if value > i32::MAX as u32 {
+        Err(Error::InvalidArgument)
+    } else {
+        Ok(())
+    }
+}
+
+fn check_table_filter(filter: &TableFilter) -> Result<(), Error> {
+    match filter {
+        TableFilter::Data(filters)
+            if filters.is_empty() || filters.len() > TableFilter::MAX_DATA_FILTERS =>
+        {
+            Err(Error::InvalidArgument)
+        }
+        TableFilter::Key(key) => check_table_key(key),
+        _ => Ok(()),
+    }
+}
+
+/// An empty string key would leave out the key argument, so it is rejected.
+fn check_table_key(key: &models::StickTableKey) -> Result<(), Error> {
+    match key {
+        models::StickTableKey::String(key) if key.is_empty() => Err(Error::InvalidArgument),
+        _ => Ok(()),
+    }
+}
+
+/// Server commands address a single backend, so `BackendId::All` is rejected.
+fn check_server_backend(backend: &BackendId) -> Result<(), Error> {
+    match backend {
+        BackendId::All => Err(Error::InvalidArgument),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MapId;
+
+    #[test]
+    fn request_bytes() {
+        assert_eq!(acl_list().to_bytes(), b"show acl\n");
+        assert_eq!(
+            map_add(MapId::Id(1), "example.com", "server1")
+                .unwrap()
+                .to_bytes(),
+            b"add map #1 example.com server1\n"
+        );
+    }
+
+    #[test]
+    fn arguments_are_escaped() {
+        assert_eq!(
+            map_set(
+                MapId::Id(1),
+                EntryRef::Key("key"),
+                "v;shutdown frontend http-in"
+            )
+            .unwrap()
+            .to_bytes(),
+            &b"set map #1 key v\\;shutdown\\ frontend\\ http-in\n"[..]
+        );
+        assert_eq!(
+            map_add(MapId::Id(1), "key", "C:\\").unwrap().to_bytes(),
+            &b"add map #1 key C:\\\\\n"[..]
+        );
+        assert_eq!(
+            acl_get(models::AclId::Id(0), "a;show info")
+                .unwrap()
+                .to_bytes(),
+            &b"get acl #0 a\\;show\\ info\n"[..]
+        );
+    }
+
+    #[test]
+    fn spaces_are_escaped() {
+        assert_eq!(
+            map_set(MapId::Id(1), EntryRef::Key("key"), "a value")
+                .unwrap()
+                .to_bytes(),
+            &b"set map #1 key a\\ value\n"[..]
+        );
+        assert_eq!(
+            map_add(MapId::Id(1), "example.com trailing", "a\tvalue")
+                .unwrap()
+                .to_bytes(),
+            &b"add map #1 example.com\\ trailing a\\\tvalue\n"[..]
+        );
+        assert_eq!(
+            map_get(MapId::File("/etc/haproxy/my map.map".into()), "a b")
+                .unwrap()
+                .to_bytes(),
+            &b"get map /etc/haproxy/my\\ map.map a\\ b\n"[..]
+        );
+        assert_eq!(
+            map_del(MapId::Id(1), EntryRef::Key("a b"))
+                .unwrap()
+                .to_bytes(),
+            &b"del map #1 a\\ b\n"[..]
+        );
+        assert_eq!(
+            acl_add(models::AclId::Id(0), "a b").unwrap().to_bytes(),
+            &b"add acl #0 a\\ b\n"[..]
+        );
+    }
+
+    #[test]
+    fn newlines_are_rejected() {
+        assert!(matches!(
+            map_set(
+                MapId::Id(1),
+                EntryRef::Key("key"),
+                "v\nshutdown frontend http-in"
+            ),
+            Err(Error::InvalidArgument)
+        ));
+        assert!(matches!(
+            acl_add(
+                models::AclId::File("/etc/acl\nshow info".into()),
+                "127.0.0.1"
+            ),
+            Err(Error::InvalidArgument)
+        ));
+        assert!(matches!(
+            disable_frontend("http\nshow info"),
+            Err(Error::InvalidArgument)
+        ));
+    }
+
+    #[test]
+    fn response_fed_in_chunks() {
+        let request = map_entries::<String, String>(MapId::Id(1)).unwrap();
+        let mut response = request.response();
+        for chunk in b"0x1 example.com server1\n0x2 exam".chunks(5) {
+            response.feed(chunk);
+        }
+        response.feed(b"ple.org server2\n\n");
+
+        let entries = response.finish().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].key, "example.org");
+    }
+
+    #[test]
+    fn prompt_response_ends_at_prompt() {
+        let request = map_entries::<String, String>(MapId::Id(1)).unwrap();
+        let mut response = request.prompt_response();
+        assert_eq!(response.feed(b"0x1 a.com >1\n"), Progress::Incomplete);
+        assert_eq!(response.feed(b"0x2 b.com s2\n"), Progress::Incomplete);
+        assert_eq!(response.feed(b"\n>"), Progress::Incomplete);
+        assert_eq!(response.feed(b" \n> "), Progress::Complete(1));
+
+        let entries = response.finish().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].value, ">1");
+
+        let mut response = request.prompt_response();
+        response.feed(b"0x1 a.com s1\n\n");
+        assert!(matches!(response.finish(), Err(Error::IoError(_))));
+    }
+
+    #[test]
+    fn prompt_scanner_needs_line_start() {
+        let mut scanner = PromptScanner::default();
+        assert_eq!(scanner.scan(b"> "), Progress::Complete(2));
+        assert_eq!(scanner.scan(b"> "), Progress::Complete(0));
+
+        let mut scanner = PromptScanner::default();
+        assert_eq!(scanner.scan(b"a > b\n>> \n"), Progress::Incomplete);
+        assert_eq!(scanner.scan(b"> "), Progress::Complete(2));
+    }
+
+    #[test]
+    fn add_server_rejects_invalid_arguments() {
+        let spec = ServerSpec::new("127.0.0.1:8080".parse().unwrap());
+        for name in &["", "s1\nshow info"] {
+            assert!(matches!(
+                add_server(BackendId::Name("servers"), name, &spec),
+                Err(Error::InvalidArgument)
+            ));
+        }
+
+        assert_eq!(
+            add_server(BackendId::Name("servers"), "two words", &spec)
+                .unwrap()
+                .to_bytes(),
+            &b"add server servers/two\\ words 127.0.0.1:8080\n"[..]
+        );
+
+        let spec = spec.keyword("inter 2s;shutdown frontend http");
+        assert!(matches!(
+            add_server(BackendId::Name("servers"), "s1", &spec),
+            Err(Error::InvalidArgument)
+        ));
+    }
+
+    #[test]
+    fn table_commands_reject_invalid_keys() {
+        for key in &["", "a\nshow info"] {
+            let key = models::StickTableKey::String(key.to_string());
+            assert!(matches!(
+                table_set("http", &key, &[("gpc0", 1)]),
+                Err(Error::InvalidArgument)
+            ));
+            assert!(matches!(
+                table_entries("http", Some(&TableFilter::Key(key))),
+                Err(Error::InvalidArgument)
+            ));
+        }
+
+        let key = models::StickTableKey::String("10.0.0.1 data.gpc0 1".to_string());
+        assert_eq!(
+            table_set("http", &key, &[("gpc0", 1)]).unwrap().to_bytes(),
+            &b"set table http key 10.0.0.1\\ data.gpc0\\ 1 data.gpc0 1\n"[..]
+        );
+    }
+
+    #[test]
+    fn response_errors() {
+        let mut response = acl_del(models::AclId::Id(0), EntryRef::Key("a"))
+            .unwrap()
+            .response();
+        response.feed(b"Key not found.\n\n");
+        assert!(matches!(response.finish(), Err(Error::NotFound)));
+
+        assert!(matches!(
+            set_server_weight(BackendId::All, ServerId::Name("s1"), Weight::Absolute(1)),
+            Err(Error::InvalidArgument)
+        ));
+    }
+}
@@ -1,6 +1,10 @@
 //! Request types for HAProxy.
 
+use crate::commands::Arg;
+use crate::models::StickTableKey;
 use std::fmt::{self, Display};
+use std::net::SocketAddr;
+use std::ops::BitOr;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum BackendId<'a> {
@@ -22,6 +26,390 @@ impl Display for BackendId<'_> {
     }
 }
 
+/// Identifier of a server within a backend.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ServerId<'a> {
+    /// Match a server ID.
+    Id(i32),
+    /// Match a server name.
+    Name(&'a str),
+}
+
+impl Display for ServerId<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ServerId::Id(id) => f.write_fmt(format_args!("#{}", id)),
+            ServerId::Name(name) => f.write_str(name),
+        }
+    }
+}
+
+/// Administrative state of a server, as set by `set server <backend>/<server> state`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ServerState {
+    /// Accept traffic normally.
+    Ready,
+    /// Stop accepting new connections, except persistent ones, while letting existing connections
+    /// finish.
+    Drain,
+    /// Stop accepting all traffic and stop health checks.
+    Maint,
+}
+
+impl Display for ServerState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ServerState::Ready => "ready",
+            ServerState::Drain => "drain",
+            ServerState::Maint => "maint",
+        })
+    }
+}
+
+/// Health check state of a server, as forced by `set server <backend>/<server> health`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HealthState {
+    Up,
+    Stopping,
+    Down,
+}
+
+impl Display for HealthState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            HealthState::Up => "up",
+            HealthState::Stopping => "stopping",
+            HealthState::Down => "down",
+        })
+    }
+}
+
+/// Agent check state of a server, as forced by `set server <backend>/<server> agent`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AgentState {
+    Up,
+    Down,
+}
+
+impl Display for AgentState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            AgentState::Up => "up",
+            AgentState::Down => "down",
+        })
+    }
+}
+
+/// Weight of a server, as set by `set server <backend>/<server> weight`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Weight {
+    /// An absolute weight between 0 and 256.
+    Absolute(u32),
+    /// A percentage of the weight the server was configured with.
+    Percent(u32),
+}
+
+impl Weight {
+    /// The largest absolute weight HAProxy accepts.
+    pub const MAX: u32 = 256;
+}
+
+impl Display for Weight {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Weight::Absolute(weight) => weight.fmt(f),
+            Weight::Percent(percent) => f.write_fmt(format_args!("{}%", percent)),
+        }
+    }
+}
+
+/// Process-wide rate limits, as set by `set rate-limit <limit> global`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RateLimit {
+    /// New connections per second.
+    Connections,
+    /// Input compression throughput in kilobytes per second.
+    HttpCompression,
+    /// New SSL sessions per second.
+    SslSessions,
+}
+
+impl Display for RateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            RateLimit::Connections => "connections",
+            RateLimit::HttpCompression => "http-compression",
+            RateLimit::SslSessions => "ssl-sessions",
+        })
+    }
+}
+
+/// Definition of a server added at runtime with `add server`.
+///
+/// Only the address is required; other settings use HAProxy's defaults unless set. Keywords
+/// without a dedicated method can be passed with [`ServerSpec::keyword`].
+///
+/// HAProxy has no keyword for tagging servers, so tags used by service discovery cannot be stored
+/// on the server itself. Encode them in the server name instead, for example `web-10.0.0.5`, and
+/// read them back from the server names reported by
+/// [`Connection::stats`](crate::Connection::stats).
+///
+/// # Examples
+/// ```
+/// use haptik::requests::ServerSpec;
+///
+/// let spec = ServerSpec::new("10.0.0.5:8080".parse().unwrap())
+///     .weight(50)
+///     .check()
+///     .maxconn(100)
+///     .keyword("inter 2s");
+/// assert_eq!(spec.to_string(), "10.0.0.5:8080 weight 50 check maxconn 100 inter 2s");
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ServerSpec<'a> {
+    addr: SocketAddr,
+    weight: Option<u32>,
+    check: bool,
+    ssl: bool,
+    maxconn: Option<u32>,
+    keywords: Vec<&'a str>,
+}
+
+impl<'a> ServerSpec<'a> {
+    /// Define a server at the given address and port.
+    pub fn new(addr: SocketAddr) -> Self {
+        ServerSpec {
+            addr,
+            weight: None,
+            check: false,
+            ssl: false,
+            maxconn: None,
+            keywords: Vec::new(),
+        }
+    }
+
+    /// Set the initial weight of the server, between 0 and [`Weight::MAX`].
+    pub fn weight(mut self, weight: u32) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Enable health checks. Checks on dynamic servers must be enabled with
+    /// [`Connection::enable_health`](crate::Connection::enable_health) once the server has been
+    /// added.
+    pub fn check(mut self) -> Self {
+        self.check = true;
+        self
+    }
+
+    /// Connect to the server over SSL/TLS.
+    pub fn ssl(mut self) -> Self {
+        self.ssl = true;
+        self
+    }
+
+    /// Limit the number of concurrent connections to the server.
+    pub fn maxconn(mut self, maxconn: u32) -> Self {
+        self.maxconn = Some(maxconn);
+        self
+    }
+
+    /// Append a server keyword and its arguments, such as `"inter 2s"` or `"cookie app1"`.
+    pub fn keyword(mut self, keyword: &'a str) -> Self {
+        self.keywords.push(keyword);
+        self
+    }
+
+    /// Whether the settings are within the bounds HAProxy accepts.
+    ///
+    /// Keywords may contain spaces between their arguments, but never a separator which would
+    /// end the command.
+    pub(crate) fn is_valid(&self) -> bool {
+        self.weight.map_or(true, |weight| weight <= Weight::MAX)
+            && self
+                .keywords
+                .iter()
+                .all(|keyword| !keyword.contains(['\n', ';']))
+    }
+}
+
+impl Display for ServerSpec<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.addr.fmt(f)?;
+        if let Some(weight) = self.weight {
+            f.write_fmt(format_args!(" weight {}", weight))?;
+        }
+        if self.check {
+            f.write_str(" check")?;
+        }
+        if self.ssl {
+            f.write_str(" ssl")?;
+        }
+        if let Some(maxconn) = self.maxconn {
+            f.write_fmt(format_args!(" maxconn {}", maxconn))?;
+        }
+        for word in self
+            .keywords
+            .iter()
+            .flat_map(|keyword| keyword.split_whitespace())
+        {
+            f.write_fmt(format_args!(" {}", Arg(word)))?;
+        }
+        Ok(())
+    }
+}
+
+/// Comparison operator for stick table data filters.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Le,
+    Lt,
+    Ge,
+    Gt,
+}
+
+impl Display for FilterOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            FilterOp::Eq => "eq",
+            FilterOp::Ne => "ne",
+            FilterOp::Le => "le",
+            FilterOp::Lt => "lt",
+            FilterOp::Ge => "ge",
+            FilterOp::Gt => "gt",
+        })
+    }
+}
+
+/// A comparison of a stick table data column with a value, such as `data.gpc0 gt 0`.
+///
+/// # Examples
+/// ```
+/// use haptik::requests::DataFilter;
+///
+/// assert_eq!(DataFilter::gt("http_req_rate", 100).to_string(), "data.http_req_rate gt 100");
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DataFilter<'a> {
+    /// The name of the data column, such as `gpc0` or `conn_rate`.
+    pub data_type: &'a str,
+    pub op: FilterOp,
+    pub value: u64,
+}
+
+impl<'a> DataFilter<'a> {
+    pub fn eq(data_type: &'a str, value: u64) -> Self {
+        DataFilter {
+            data_type,
+            op: FilterOp::Eq,
+            value,
+        }
+    }
+
+    pub fn ne(data_type: &'a str, value: u64) -> Self {
+        DataFilter {
+            data_type,
+            op: FilterOp::Ne,
+            value,
+        }
+    }
+
+    pub fn le(data_type: &'a str, value: u64) -> Self {
+        DataFilter {
+            data_type,
+            op: FilterOp::Le,
+            value,
+        }
+    }
+
+    pub fn lt(data_type: &'a str, value: u64) -> Self {
+        DataFilter {
+            data_type,
+            op: FilterOp::Lt,
+            value,
+        }
+    }
+
+    pub fn ge(data_type: &'a str, value: u64) -> Self {
+        DataFilter {
+            data_type,
+            op: FilterOp::Ge,
+            value,
+        }
+    }
+
+    pub fn gt(data_type: &'a str, value: u64) -> Self {
+        DataFilter {
+            data_type,
+            op: FilterOp::Gt,
+            value,
+        }
+    }
+}
+
+impl Display for DataFilter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_fmt(format_args!(
+            "data.{} {} {}",
+            Arg(self.data_type),
+            self.op,
+            self.value
+        ))
+    }
+}
+
+/// Selects the stick table entries a command applies to.
+///
+/// HAProxy accepts either a single key or up to four data filters, which must all match.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TableFilter<'a> {
+    /// Match the entry with the given key.
+    Key(StickTableKey),
+    /// Match entries for which every filter matches.
+    Data(Vec<DataFilter<'a>>),
+}
+
+impl TableFilter<'_> {
+    /// The largest number of data filters HAProxy accepts in one command.
+    pub const MAX_DATA_FILTERS: usize = 4;
+}
+
+impl Display for TableFilter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TableFilter::Key(key) => f.write_fmt(format_args!("key {}", Arg(key))),
+            TableFilter::Data(filters) => {
+                let mut separator = "";
+                for filter in filters {
+                    f.write_fmt(format_args!("{}{}", separator, filter))?;
+                    separator = " ";
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Reference to an entry of an ACL or map, either by its key or by its element pointer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EntryRef<'a> {
+    /// Match entries by key.
+    Key(&'a str),
+    /// Match the entry with the given element pointer, as reported in the entry's `id` field.
+    Pointer(u64),
+}
+
+impl Display for EntryRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EntryRef::Key(key) => f.write_str(key),
+            EntryRef::Pointer(id) => f.write_fmt(format_args!("#0x{:x}", id)),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ErrorFlag {
     /// Match request and response errors.
@@ -31,3 +419,183 @@ pub enum ErrorFlag {
     /// Match only response errors.
     Response,
 }
+
+/// A set of object types to report in `show stat` output.
+///
+/// Types can be combined with `|`, for example `StatObjects::BACKEND | StatObjects::SERVER`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct StatObjects(u8);
+
+impl StatObjects {
+    /// Match frontends.
+    pub const FRONTEND: StatObjects = StatObjects(1);
+    /// Match backends.
+    pub const BACKEND: StatObjects = StatObjects(2);
+    /// Match servers.
+    pub const SERVER: StatObjects = StatObjects(4);
+    /// Match all object types.
+    pub const ALL: StatObjects = StatObjects(7);
+
+    /// Check whether all types in `other` are included in this set.
+    pub fn contains(self, other: StatObjects) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for StatObjects {
+    type Output = StatObjects;
+
+    fn bitor(self, rhs: StatObjects) -> StatObjects {
+        StatObjects(self.0 | rhs.0)
+    }
+}
+
+impl Display for StatObjects {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if *self == StatObjects::ALL {
+            f.write_str("-1")
+        } else {
+            self.0.fmt(f)
+        }
+    }
+}
+
+/// Filter for `show stat` selecting a proxy, the object types within it, and a server.
+///
+/// The default filter matches every object, which is equivalent to an unfiltered `show stat`.
+///
+/// # Examples
+/// ```
+/// use haptik::requests::{BackendId, StatFilter, StatObjects};
+///
+/// let filter = StatFilter {
+///     proxy: BackendId::Name("servers"),
+///     objects: StatObjects::BACKEND | StatObjects::SERVER,
+///     server: None,
+/// };
+/// assert_eq!(filter.to_string(), "servers 6 -1");
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StatFilter<'a> {
+    /// The proxy to report on.
+    pub proxy: BackendId<'a>,
+    /// The object types to report.
+    pub objects: StatObjects,
+    /// The numeric ID of the server to report on, or `None` for all servers.
+    pub server: Option<i32>,
+}
+
+impl Default for StatFilter<'_> {
+    fn default() -> Self {
+        StatFilter {
+            proxy: BackendId::All,
+            objects: StatObjects::ALL,
+            server: None,
+        }
+    }
+}
+
+impl Display for StatFilter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_fmt(format_args!(
+            "{} {} {}",
+            Arg(&self.proxy),
+            self.objects,
+            self.server.unwrap_or(-1)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_id_display() {
+        assert_eq!(ServerId::Id(1).to_string(), "#1");
+        assert_eq!(ServerId::Name("server1").to_string(), "server1");
+    }
+
+    #[test]
+    fn server_states_display() {
+        assert_eq!(ServerState::Ready.to_string(), "ready");
+        assert_eq!(ServerState::Drain.to_string(), "drain");
+        assert_eq!(ServerState::Maint.to_string(), "maint");
+        assert_eq!(HealthState::Stopping.to_string(), "stopping");
+        assert_eq!(AgentState::Down.to_string(), "down");
+    }
+
+    #[test]
+    fn weight_display() {
+        assert_eq!(Weight::Absolute(10).to_string(), "10");
+        assert_eq!(Weight::Percent(50).to_string(), "50%");
+    }
+
+    #[test]
+    fn server_spec_display() {
+        let spec = ServerSpec::new("[::1]:8443".parse().unwrap())
+            .ssl()
+            .keyword("verify none");
+        assert_eq!(spec.to_string(), "[::1]:8443 ssl verify none");
+        assert_eq!(
+            ServerSpec::new("127.0.0.1:80".parse().unwrap()).to_string(),
+            "127.0.0.1:80"
+        );
+    }
+
+    #[test]
+    fn table_filter_display() {
+        let filter = TableFilter::Data(vec![
+            DataFilter::ge("gpc0", 1),
+            DataFilter::lt("conn_rate", 10),
+        ]);
+        assert_eq!(filter.to_string(), "data.gpc0 ge 1 data.conn_rate lt 10");
+
+        let filter = TableFilter::Key(StickTableKey::Integer(5));
+        assert_eq!(filter.to_string(), "key 5");
+    }
+
+    #[test]
+    fn rate_limit_display() {
+        assert_eq!(RateLimit::HttpCompression.to_string(), "http-compression");
+        assert_eq!(RateLimit::SslSessions.to_string(), "ssl-sessions");
+    }
+
+    #[test]
+    fn entry_ref_display() {
+        assert_eq!(EntryRef::Key("example.com").to_string(), "example.com");
+        assert_eq!(
+            EntryRef::Pointer(0x55fd35e0e190).to_string(),
+            "#0x55fd35e0e190"
+        );
+    }
+
+    #[test]
+    fn stat_objects_display() {
+        assert_eq!(StatObjects::FRONTEND.to_string(), "1");
+        assert_eq!(
+            (StatObjects::FRONTEND | StatObjects::SERVER).to_string(),
+            "5"
+        );
+        assert_eq!(
+            (StatObjects::FRONTEND | StatObjects::BACKEND | StatObjects::SERVER).to_string(),
+            "-1"
+        );
+        assert!(StatObjects::ALL.contains(StatObjects::BACKEND));
+        assert!(!StatObjects::SERVER.contains(StatObjects::BACKEND));
+    }
+
+    #[test]
+    fn stat_filter_display() {
+        assert_eq!(StatFilter::default().to_string(), "-1 -1 -1");
+        assert_eq!(
+            StatFilter {
+                proxy: BackendId::Id(2),
+                objects: StatObjects::SERVER,
+                server: Some(1),
+            }
+            .to_string(),
+            "2 4 1"
+        );
+    }
+}
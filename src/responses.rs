@@ -1,8 +1,13 @@
 //! Parse responses from HAProxy sockets.
 
 use crate::errors::Error;
+use crate::models::{StickTableKey, StickTableType};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::net::IpAddr;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Acl {
@@ -15,29 +20,179 @@ impl FromStr for Acl {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.splitn(3, ' ').collect();
-        if let [id, reference, description] = parts.as_slice() {
-            let reference_option = match reference {
-                &"()" => None,
-                reference => {
-                    if reference.len() < 3 {
-                        return Err(Error::ParseFailure);
-                    }
-                    Some(reference[1..reference.len() - 1].into())
+        let (id, reference, description) = parse_pattern_ref(s)?;
+        Ok(Acl {
+            id,
+            reference,
+            description,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Map {
+    pub id: i32,
+    pub reference: Option<String>,
+    pub description: String,
+}
+
+impl FromStr for Map {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (id, reference, description) = parse_pattern_ref(s)?;
+        Ok(Map {
+            id,
+            reference,
+            description,
+        })
+    }
+}
+
+/// Parse a line of `show acl` or `show map` output into its ID, reference and description.
+fn parse_pattern_ref(s: &str) -> Result<(i32, Option<String>, String), Error> {
+    let parts: Vec<&str> = s.splitn(3, ' ').collect();
+    if let [id, reference, description] = parts.as_slice() {
+        let reference_option = match reference {
+            &"()" => None,
+            reference => {
+                if reference.len() < 3 {
+                    return Err(Error::ParseFailure);
                 }
-            };
+                Some(reference[1..reference.len() - 1].into())
+            }
+        };
 
-            Ok(Acl {
-                id: i32::from_str(id)?,
-                reference: reference_option,
-                description: description.to_string(),
-            })
-        } else {
-            Err(Error::ParseFailure)
+        Ok((
+            i32::from_str(id)?,
+            reference_option,
+            description.to_string(),
+        ))
+    } else {
+        Err(Error::ParseFailure)
+    }
+}
+
+/// The result of matching a value against an ACL with `get acl`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AclMatch {
+    /// The matching method of the ACL, such as `str` or `ip`.
+    pub match_type: String,
+    pub case_sensitive: bool,
+    /// Whether the value matched the ACL.
+    pub matched: bool,
+    /// The pattern which matched the value.
+    pub pattern: Option<String>,
+}
+
+impl FromStr for AclMatch {
+    type Err = Error;
+
+    /// Parse a line such as `type=ip, case=sensitive, match=yes, idx=tree, pattern="127.0.0.1"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut acl_match = AclMatch {
+            match_type: String::new(),
+            case_sensitive: true,
+            matched: false,
+            pattern: None,
+        };
+
+        for (name, value) in parse_match_fields(s)? {
+            match name {
+                "type" => acl_match.match_type = value,
+                "case" => acl_match.case_sensitive = value == "sensitive",
+                "match" => acl_match.matched = value == "yes",
+                "pattern" => acl_match.pattern = Some(value),
+                _ => {}
+            }
         }
+
+        if acl_match.match_type.is_empty() {
+            return Err(Error::ParseFailure);
+        }
+
+        Ok(acl_match)
+    }
+}
+
+/// The result of looking up a key in a map with `get map`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MapMatch {
+    /// The matching method of the map, such as `str` or `ip`.
+    pub match_type: String,
+    pub case_sensitive: bool,
+    /// Whether a matching entry was found.
+    pub found: bool,
+    /// The key of the matching entry.
+    pub key: Option<String>,
+    /// The value of the matching entry.
+    pub value: Option<String>,
+}
+
+impl FromStr for MapMatch {
+    type Err = Error;
+
+    /// Parse a line such as
+    /// `type=str, case=sensitive, found=yes, idx=tree, key="a", value="b", type="str"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut map_match = MapMatch {
+            match_type: String::new(),
+            case_sensitive: true,
+            found: false,
+            key: None,
+            value: None,
+        };
+
+        for (name, value) in parse_match_fields(s)? {
+            match name {
+                // The value type is also reported as `type`; only the first is the match type.
+                "type" if map_match.match_type.is_empty() => map_match.match_type = value,
+                "case" => map_match.case_sensitive = value == "sensitive",
+                "found" => map_match.found = value == "yes",
+                "key" => map_match.key = Some(value),
+                "value" => map_match.value = Some(value),
+                _ => {}
+            }
+        }
+
+        if map_match.match_type.is_empty() {
+            return Err(Error::ParseFailure);
+        }
+
+        Ok(map_match)
     }
 }
 
+/// Split the comma-separated `name=value` pairs of `get map` and `get acl` output, removing quotes
+/// around values.
+fn parse_match_fields(s: &str) -> Result<Vec<(&str, String)>, Error> {
+    let mut fields = Vec::new();
+    let mut rest = s;
+
+    while !rest.is_empty() {
+        let (name, after_name) = rest.split_at(rest.find('=').ok_or(Error::ParseFailure)?);
+        let after_name = &after_name[1..];
+
+        let (value, after_value) = if let Some(quoted) = after_name.strip_prefix('"') {
+            let end = quoted
+                .find("\", ")
+                .unwrap_or_else(|| quoted.len().saturating_sub(1));
+            if !quoted[end..].starts_with('"') {
+                return Err(Error::ParseFailure);
+            }
+            (quoted[..end].to_string(), &quoted[end + 1..])
+        } else {
+            let end = after_name.find(", ").unwrap_or(after_name.len());
+            (after_name[..end].to_string(), &after_name[end..])
+        };
+
+        fields.push((name, value));
+        rest = after_value.strip_prefix(", ").unwrap_or(after_value);
+    }
+
+    Ok(fields)
+}
+
 #[derive(Debug, Hash, Eq, PartialEq)]
 pub enum Level {
     Admin,
@@ -140,6 +295,1234 @@ impl FromStr for CliSocketProcesses {
     }
 }
 
+/// The kind of proxy object described by a row of `show stat` output.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StatType {
+    Frontend,
+    Backend,
+    Server,
+    Listener,
+}
+
+impl FromStr for StatType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "0" => Ok(StatType::Frontend),
+            "1" => Ok(StatType::Backend),
+            "2" => Ok(StatType::Server),
+            "3" => Ok(StatType::Listener),
+            _ => Err(Error::ParseFailure),
+        }
+    }
+}
+
+/// A single row of `show stat` output describing a frontend, backend, server or listener.
+///
+/// HAProxy adds columns between releases and leaves many of them empty depending on the object
+/// type, so all statistics are optional. Columns that are not modeled here are kept in `extra`,
+/// keyed by their column name.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StatRow {
+    /// Proxy name.
+    pub pxname: String,
+    /// Service name (`FRONTEND`, `BACKEND`, or the server or listener name).
+    pub svname: String,
+    /// The kind of object described by this row.
+    pub stat_type: Option<StatType>,
+    pub qcur: Option<u64>,
+    pub qmax: Option<u64>,
+    pub scur: Option<u64>,
+    pub smax: Option<u64>,
+    pub slim: Option<u64>,
+    pub stot: Option<u64>,
+    pub bin: Option<u64>,
+    pub bout: Option<u64>,
+    pub dreq: Option<u64>,
+    pub dresp: Option<u64>,
+    pub ereq: Option<u64>,
+    pub econ: Option<u64>,
+    pub eresp: Option<u64>,
+    pub wretr: Option<u64>,
+    pub wredis: Option<u64>,
+    pub status: Option<String>,
+    pub weight: Option<u32>,
+    pub act: Option<u32>,
+    pub bck: Option<u32>,
+    pub chkfail: Option<u64>,
+    pub chkdown: Option<u64>,
+    pub lastchg: Option<u64>,
+    pub downtime: Option<u64>,
+    pub qlimit: Option<u64>,
+    pub pid: Option<u32>,
+    pub iid: Option<u32>,
+    pub sid: Option<u32>,
+    pub throttle: Option<u32>,
+    pub lbtot: Option<u64>,
+    pub tracked: Option<String>,
+    pub rate: Option<u64>,
+    pub rate_lim: Option<u64>,
+    pub rate_max: Option<u64>,
+    pub check_status: Option<String>,
+    pub check_code: Option<u32>,
+    pub check_duration: Option<u64>,
+    pub hrsp_1xx: Option<u64>,
+    pub hrsp_2xx: Option<u64>,
+    pub hrsp_3xx: Option<u64>,
+    pub hrsp_4xx: Option<u64>,
+    pub hrsp_5xx: Option<u64>,
+    pub hrsp_other: Option<u64>,
+    pub hanafail: Option<u64>,
+    pub req_rate: Option<u64>,
+    pub req_rate_max: Option<u64>,
+    pub req_tot: Option<u64>,
+    pub cli_abrt: Option<u64>,
+    pub srv_abrt: Option<u64>,
+    pub comp_in: Option<u64>,
+    pub comp_out: Option<u64>,
+    pub comp_byp: Option<u64>,
+    pub comp_rsp: Option<u64>,
+    /// Seconds since the last session was assigned; HAProxy reports `-1` if there was none.
+    pub lastsess: Option<i64>,
+    pub qtime: Option<u64>,
+    pub ctime: Option<u64>,
+    pub rtime: Option<u64>,
+    pub ttime: Option<u64>,
+    pub mode: Option<String>,
+    /// Columns not modeled above, keyed by column name.
+    pub extra: HashMap<String, String>,
+}
+
+impl StatRow {
+    /// Build a row from `(column name, value)` pairs.
+    ///
+    /// Empty values are treated as missing. Unknown columns are stored in `extra`.
+    pub fn from_fields<'a, I: IntoIterator<Item = (&'a str, &'a str)>>(
+        fields: I,
+    ) -> Result<Self, Error> {
+        let mut row = StatRow::default();
+
+        for (name, value) in fields {
+            if name.is_empty() || value.is_empty() {
+                continue;
+            }
+
+            match name {
+                "pxname" => row.pxname = value.to_string(),
+                "svname" => row.svname = value.to_string(),
+                "type" => row.stat_type = Some(StatType::from_str(value)?),
+                "qcur" => row.qcur = Some(parse_field(value)?),
+                "qmax" => row.qmax = Some(parse_field(value)?),
+                "scur" => row.scur = Some(parse_field(value)?),
+                "smax" => row.smax = Some(parse_field(value)?),
+                "slim" => row.slim = Some(parse_field(value)?),
+                "stot" => row.stot = Some(parse_field(value)?),
+                "bin" => row.bin = Some(parse_field(value)?),
+                "bout" => row.bout = Some(parse_field(value)?),
+                "dreq" => row.dreq = Some(parse_field(value)?),
+                "dresp" => row.dresp = Some(parse_field(value)?),
+                "ereq" => row.ereq = Some(parse_field(value)?),
+                "econ" => row.econ = Some(parse_field(value)?),
+                "eresp" => row.eresp = Some(parse_field(value)?),
+                "wretr" => row.wretr = Some(parse_field(value)?),
+                "wredis" => row.wredis = Some(parse_field(value)?),
+                "status" => row.status = Some(value.to_string()),
+                "weight" => row.weight = Some(parse_field(value)?),
+                "act" => row.act = Some(parse_field(value)?),
+                "bck" => row.bck = Some(parse_field(value)?),
+                "chkfail" => row.chkfail = Some(parse_field(value)?),
+                "chkdown" => row.chkdown = Some(parse_field(value)?),
+                "lastchg" => row.lastchg = Some(parse_field(value)?),
+                "downtime" => row.downtime = Some(parse_field(value)?),
+                "qlimit" => row.qlimit = Some(parse_field(value)?),
+                "pid" => row.pid = Some(parse_field(value)?),
+                "iid" => row.iid = Some(parse_field(value)?),
+                "sid" => row.sid = Some(parse_field(value)?),
+                "throttle" => row.throttle = Some(parse_field(value)?),
+                "lbtot" => row.lbtot = Some(parse_field(value)?),
+                "tracked" => row.tracked = Some(value.to_string()),
+                "rate" => row.rate = Some(parse_field(value)?),
+                "rate_lim" => row.rate_lim = Some(parse_field(value)?),
+                "rate_max" => row.rate_max = Some(parse_field(value)?),
+                "check_status" => row.check_status = Some(value.to_string()),
+                "check_code" => row.check_code = Some(parse_field(value)?),
+                "check_duration" => row.check_duration = Some(parse_field(value)?),
+                "hrsp_1xx" => row.hrsp_1xx = Some(parse_field(value)?),
+                "hrsp_2xx" => row.hrsp_2xx = Some(parse_field(value)?),
+                "hrsp_3xx" => row.hrsp_3xx = Some(parse_field(value)?),
+                "hrsp_4xx" => row.hrsp_4xx = Some(parse_field(value)?),
+                "hrsp_5xx" => row.hrsp_5xx = Some(parse_field(value)?),
+                "hrsp_other" => row.hrsp_other = Some(parse_field(value)?),
+                "hanafail" => row.hanafail = Some(parse_field(value)?),
+                "req_rate" => row.req_rate = Some(parse_field(value)?),
+                "req_rate_max" => row.req_rate_max = Some(parse_field(value)?),
+                "req_tot" => row.req_tot = Some(parse_field(value)?),
+                "cli_abrt" => row.cli_abrt = Some(parse_field(value)?),
+                "srv_abrt" => row.srv_abrt = Some(parse_field(value)?),
+                "comp_in" => row.comp_in = Some(parse_field(value)?),
+                "comp_out" => row.comp_out = Some(parse_field(value)?),
+                "comp_byp" => row.comp_byp = Some(parse_field(value)?),
+                "comp_rsp" => row.comp_rsp = Some(parse_field(value)?),
+                "lastsess" => row.lastsess = Some(parse_field(value)?),
+                "qtime" => row.qtime = Some(parse_field(value)?),
+                "ctime" => row.ctime = Some(parse_field(value)?),
+                "rtime" => row.rtime = Some(parse_field(value)?),
+                "ttime" => row.ttime = Some(parse_field(value)?),
+                "mode" => row.mode = Some(value.to_string()),
+                name => {
+                    row.extra.insert(name.to_string(), value.to_string());
+                }
+            }
+        }
+
+        if row.pxname.is_empty() || row.svname.is_empty() {
+            return Err(Error::ParseFailure);
+        }
+
+        Ok(row)
+    }
+}
+
+/// Process information reported by `show info`.
+///
+/// Fields not present in the running HAProxy version are `None`. Keys not modeled here are kept in
+/// `extra`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Info {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub release_date: Option<String>,
+    pub nbthread: Option<u32>,
+    pub nbproc: Option<u32>,
+    pub process_num: Option<u32>,
+    pub pid: Option<u32>,
+    pub uptime_sec: Option<u64>,
+    pub memmax_mb: Option<u64>,
+    pub pool_alloc_mb: Option<u64>,
+    pub pool_used_mb: Option<u64>,
+    pub ulimit_n: Option<u64>,
+    pub maxsock: Option<u64>,
+    pub maxconn: Option<u64>,
+    pub hard_maxconn: Option<u64>,
+    pub curr_conns: Option<u64>,
+    pub cum_conns: Option<u64>,
+    pub cum_req: Option<u64>,
+    pub max_ssl_conns: Option<u64>,
+    pub curr_ssl_conns: Option<u64>,
+    pub cum_ssl_conns: Option<u64>,
+    pub maxpipes: Option<u64>,
+    pub pipes_used: Option<u64>,
+    pub pipes_free: Option<u64>,
+    pub conn_rate: Option<u64>,
+    pub conn_rate_limit: Option<u64>,
+    pub max_conn_rate: Option<u64>,
+    pub sess_rate: Option<u64>,
+    pub sess_rate_limit: Option<u64>,
+    pub max_sess_rate: Option<u64>,
+    pub ssl_rate: Option<u64>,
+    pub ssl_rate_limit: Option<u64>,
+    pub max_ssl_rate: Option<u64>,
+    pub tasks: Option<u64>,
+    pub run_queue: Option<u64>,
+    pub idle_pct: Option<u32>,
+    pub node: Option<String>,
+    pub description: Option<String>,
+    /// Whether the process is stopping, for example after a soft-stop.
+    pub stopping: Option<bool>,
+    pub jobs: Option<u64>,
+    pub unstoppable_jobs: Option<u64>,
+    pub listeners: Option<u64>,
+    pub active_peers: Option<u64>,
+    pub connected_peers: Option<u64>,
+    pub dropped_logs: Option<u64>,
+    pub busy_polling: Option<u64>,
+    pub failed_resolutions: Option<u64>,
+    pub total_bytes_out: Option<u64>,
+    pub bytes_out_rate: Option<u64>,
+    /// Keys not modeled above, keyed by the name reported by HAProxy.
+    pub extra: HashMap<String, String>,
+}
+
+impl Info {
+    /// Build process information from `(key, value)` pairs.
+    ///
+    /// Empty values are treated as missing. Unknown keys are stored in `extra`.
+    pub fn from_fields<'a, I: IntoIterator<Item = (&'a str, &'a str)>>(
+        fields: I,
+    ) -> Result<Self, Error> {
+        let mut info = Info::default();
+
+        for (name, value) in fields {
+            if name.is_empty() || value.is_empty() {
+                continue;
+            }
+
+            match name {
+                "Name" => info.name = Some(value.to_string()),
+                "Version" => info.version = Some(value.to_string()),
+                "Release_date" => info.release_date = Some(value.to_string()),
+                "Nbthread" => info.nbthread = Some(parse_field(value)?),
+                "Nbproc" => info.nbproc = Some(parse_field(value)?),
+                "Process_num" => info.process_num = Some(parse_field(value)?),
+                "Pid" => info.pid = Some(parse_field(value)?),
+                "Uptime_sec" => info.uptime_sec = Some(parse_field(value)?),
+                "Memmax_MB" => info.memmax_mb = Some(parse_field(value)?),
+                "PoolAlloc_MB" => info.pool_alloc_mb = Some(parse_field(value)?),
+                "PoolUsed_MB" => info.pool_used_mb = Some(parse_field(value)?),
+                "Ulimit-n" => info.ulimit_n = Some(parse_field(value)?),
+                "Maxsock" => info.maxsock = Some(parse_field(value)?),
+                "Maxconn" => info.maxconn = Some(parse_field(value)?),
+                "Hard_maxconn" => info.hard_maxconn = Some(parse_field(value)?),
+                "CurrConns" => info.curr_conns = Some(parse_field(value)?),
+                "CumConns" => info.cum_conns = Some(parse_field(value)?),
+                "CumReq" => info.cum_req = Some(parse_field(value)?),
+                "MaxSslConns" => info.max_ssl_conns = Some(parse_field(value)?),
+                "CurrSslConns" => info.curr_ssl_conns = Some(parse_field(value)?),
+                "CumSslConns" => info.cum_ssl_conns = Some(parse_field(value)?),
+                "Maxpipes" => info.maxpipes = Some(parse_field(value)?),
+                "PipesUsed" => info.pipes_used = Some(parse_field(value)?),
+                "PipesFree" => info.pipes_free = Some(parse_field(value)?),
+                "ConnRate" => info.conn_rate = Some(parse_field(value)?),
+                "ConnRateLimit" => info.conn_rate_limit = Some(parse_field(value)?),
+                "MaxConnRate" => info.max_conn_rate = Some(parse_field(value)?),
+                "SessRate" => info.sess_rate = Some(parse_field(value)?),
+                "SessRateLimit" => info.sess_rate_limit = Some(parse_field(value)?),
+                "MaxSessRate" => info.max_sess_rate = Some(parse_field(value)?),
+                "SslRate" => info.ssl_rate = Some(parse_field(value)?),
+                "SslRateLimit" => info.ssl_rate_limit = Some(parse_field(value)?),
+                "MaxSslRate" => info.max_ssl_rate = Some(parse_field(value)?),
+                "Tasks" => info.tasks = Some(parse_field(value)?),
+                "Run_queue" => info.run_queue = Some(parse_field(value)?),
+                "Idle_pct" => info.idle_pct = Some(parse_field(value)?),
+                "node" => info.node = Some(value.to_string()),
+                "description" => info.description = Some(value.to_string()),
+                "Stopping" => info.stopping = Some(parse_field::<u8>(value)? != 0),
+                "Jobs" => info.jobs = Some(parse_field(value)?),
+                "Unstoppable Jobs" => info.unstoppable_jobs = Some(parse_field(value)?),
+                "Listeners" => info.listeners = Some(parse_field(value)?),
+                "ActivePeers" => info.active_peers = Some(parse_field(value)?),
+                "ConnectedPeers" => info.connected_peers = Some(parse_field(value)?),
+                "DroppedLogs" => info.dropped_logs = Some(parse_field(value)?),
+                "BusyPolling" => info.busy_polling = Some(parse_field(value)?),
+                "FailedResolutions" => info.failed_resolutions = Some(parse_field(value)?),
+                "TotalBytesOut" => info.total_bytes_out = Some(parse_field(value)?),
+                "BytesOutRate" => info.bytes_out_rate = Some(parse_field(value)?),
+                name => {
+                    info.extra.insert(name.to_string(), value.to_string());
+                }
+            }
+        }
+
+        Ok(info)
+    }
+}
+
+/// Where the value of a statistics field comes from.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FieldOrigin {
+    /// A metric measured by HAProxy.
+    Metric,
+    /// A status reported by HAProxy.
+    Status,
+    /// A key identifying the object, such as a proxy name.
+    Key,
+    /// A value taken from the configuration.
+    Config,
+    /// A value describing the product, such as its version.
+    Product,
+    Unknown,
+}
+
+impl FromStr for FieldOrigin {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Metric" => Ok(FieldOrigin::Metric),
+            "Status" => Ok(FieldOrigin::Status),
+            "Key" => Ok(FieldOrigin::Key),
+            "Config" => Ok(FieldOrigin::Config),
+            "Product" => Ok(FieldOrigin::Product),
+            "Unknown" => Ok(FieldOrigin::Unknown),
+            _ => Err(Error::ParseFailure),
+        }
+    }
+}
+
+impl TryFrom<char> for FieldOrigin {
+    type Error = Error;
+
+    /// Convert from the single-character code used in `typed` output.
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'M' => Ok(FieldOrigin::Metric),
+            'S' => Ok(FieldOrigin::Status),
+            'K' => Ok(FieldOrigin::Key),
+            'C' => Ok(FieldOrigin::Config),
+            'P' => Ok(FieldOrigin::Product),
+            _ => Err(Error::ParseFailure),
+        }
+    }
+}
+
+/// What kind of quantity a statistics field measures.
+///
+/// Exporters can use this to pick a metric type; for example, `Counter` fields only increase while
+/// `Gauge` fields may go up and down.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FieldNature {
+    Age,
+    Avg,
+    Counter,
+    Duration,
+    Gauge,
+    Limit,
+    Max,
+    Min,
+    Name,
+    Output,
+    Rate,
+    Time,
+    Unknown,
+}
+
+impl FromStr for FieldNature {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Age" => Ok(FieldNature::Age),
+            "Avg" => Ok(FieldNature::Avg),
+            "Counter" => Ok(FieldNature::Counter),
+            "Duration" => Ok(FieldNature::Duration),
+            "Gauge" => Ok(FieldNature::Gauge),
+            "Limit" => Ok(FieldNature::Limit),
+            "Max" => Ok(FieldNature::Max),
+            "Min" => Ok(FieldNature::Min),
+            "Name" => Ok(FieldNature::Name),
+            "Output" => Ok(FieldNature::Output),
+            "Rate" => Ok(FieldNature::Rate),
+            "Time" => Ok(FieldNature::Time),
+            "Unknown" => Ok(FieldNature::Unknown),
+            _ => Err(Error::ParseFailure),
+        }
+    }
+}
+
+impl TryFrom<char> for FieldNature {
+    type Error = Error;
+
+    /// Convert from the single-character code used in `typed` output.
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'A' => Ok(FieldNature::Age),
+            'a' => Ok(FieldNature::Avg),
+            'C' => Ok(FieldNature::Counter),
+            'D' => Ok(FieldNature::Duration),
+            'G' => Ok(FieldNature::Gauge),
+            'L' => Ok(FieldNature::Limit),
+            'M' => Ok(FieldNature::Max),
+            'm' => Ok(FieldNature::Min),
+            'N' => Ok(FieldNature::Name),
+            'O' => Ok(FieldNature::Output),
+            'R' => Ok(FieldNature::Rate),
+            'T' => Ok(FieldNature::Time),
+            _ => Err(Error::ParseFailure),
+        }
+    }
+}
+
+/// The extent over which a statistics field is measured.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FieldScope {
+    /// The value is shared by all nodes of a cluster.
+    Cluster,
+    /// The value is specific to the process reporting it.
+    Process,
+    /// The value is specific to the service (proxy, server, etc.) reporting it.
+    Service,
+    /// The value is shared by all processes on the system.
+    System,
+    Unknown,
+}
+
+impl FromStr for FieldScope {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Cluster" => Ok(FieldScope::Cluster),
+            "Process" => Ok(FieldScope::Process),
+            "Service" => Ok(FieldScope::Service),
+            "System" => Ok(FieldScope::System),
+            "Unknown" => Ok(FieldScope::Unknown),
+            _ => Err(Error::ParseFailure),
+        }
+    }
+}
+
+impl TryFrom<char> for FieldScope {
+    type Error = Error;
+
+    /// Convert from the single-character code used in `typed` output.
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'C' => Ok(FieldScope::Cluster),
+            'P' => Ok(FieldScope::Process),
+            'S' => Ok(FieldScope::Service),
+            's' => Ok(FieldScope::System),
+            _ => Err(Error::ParseFailure),
+        }
+    }
+}
+
+/// Metadata HAProxy reports alongside the value of a statistics field.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FieldTags {
+    pub origin: FieldOrigin,
+    pub nature: FieldNature,
+    pub scope: FieldScope,
+}
+
+/// Parse the tag column of `typed` output, such as `MGP`.
+///
+/// The first three characters hold the origin, nature and scope. Newer HAProxy versions may append
+/// further characters, which are ignored.
+impl FromStr for FieldTags {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(origin), Some(nature), Some(scope)) => Ok(FieldTags {
+                origin: FieldOrigin::try_from(origin)?,
+                nature: FieldNature::try_from(nature)?,
+                scope: FieldScope::try_from(scope)?,
+            }),
+            _ => Err(Error::ParseFailure),
+        }
+    }
+}
+
+/// A value decoded from HAProxy output, along with the tags reported for each of its fields.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tagged<T> {
+    pub value: T,
+    /// Field tags keyed by field name.
+    pub tags: HashMap<String, FieldTags>,
+}
+
+/// The value of a field in `typed` output, converted according to the type HAProxy reported.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypedField {
+    S32(i32),
+    S64(i64),
+    U32(u32),
+    U64(u64),
+    Float(f64),
+    Str(String),
+}
+
+impl TypedField {
+    /// Parse `value` according to a type name such as `u32` or `str`.
+    pub fn parse(field_type: &str, value: &str) -> Result<Self, Error> {
+        match field_type {
+            "s32" => Ok(TypedField::S32(parse_field(value)?)),
+            "s64" => Ok(TypedField::S64(parse_field(value)?)),
+            "u32" => Ok(TypedField::U32(parse_field(value)?)),
+            "u64" => Ok(TypedField::U64(parse_field(value)?)),
+            "flt" => Ok(TypedField::Float(parse_field(value)?)),
+            "str" => Ok(TypedField::Str(value.to_string())),
+            _ => Err(Error::ParseFailure),
+        }
+    }
+}
+
+/// A single field of `show stat typed` output.
+///
+/// Each line of output reports one field of one object for one process, so a statistics row is
+/// spread over many `TypedStat` values sharing the same `stat_type`, `proxy_id`, `object_id` and
+/// `process`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedStat {
+    pub stat_type: StatType,
+    pub proxy_id: u32,
+    pub object_id: u32,
+    /// Position of the field in the CSV output of `show stat`.
+    pub position: u32,
+    pub name: String,
+    /// The number of the process reporting this field.
+    pub process: u32,
+    pub tags: FieldTags,
+    pub value: TypedField,
+}
+
+impl FromStr for TypedStat {
+    type Err = Error;
+
+    /// Parse a line such as `F.2.0.4.scur.1:MGP:u32:0`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (descriptor, tags, value) = split_typed_line(s)?;
+        let parts: Vec<&str> = descriptor.splitn(6, '.').collect();
+        match parts.as_slice() {
+            [stat_type, proxy_id, object_id, position, name, process] => Ok(TypedStat {
+                stat_type: match *stat_type {
+                    "F" => StatType::Frontend,
+                    "B" => StatType::Backend,
+                    "S" => StatType::Server,
+                    "L" => StatType::Listener,
+                    _ => return Err(Error::ParseFailure),
+                },
+                proxy_id: parse_field(proxy_id)?,
+                object_id: parse_field(object_id)?,
+                position: parse_field(position)?,
+                name: name.to_string(),
+                process: parse_field(process)?,
+                tags,
+                value,
+            }),
+            _ => Err(Error::ParseFailure),
+        }
+    }
+}
+
+/// A single field of `show info typed` output.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedInfo {
+    /// Position of the field in the output of `show info`.
+    pub position: u32,
+    pub name: String,
+    /// The number of the process reporting this field.
+    pub process: u32,
+    pub tags: FieldTags,
+    pub value: TypedField,
+}
+
+impl FromStr for TypedInfo {
+    type Err = Error;
+
+    /// Parse a line such as `0.Name.1:POS:str:HAProxy`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (descriptor, tags, value) = split_typed_line(s)?;
+        let parts: Vec<&str> = descriptor.splitn(3, '.').collect();
+        match parts.as_slice() {
+            [position, name, process] => Ok(TypedInfo {
+                position: parse_field(position)?,
+                name: name.to_string(),
+                process: parse_field(process)?,
+                tags,
+                value,
+            }),
+            _ => Err(Error::ParseFailure),
+        }
+    }
+}
+
+/// Split a line of `typed` output into its descriptor, tags and value.
+fn split_typed_line(s: &str) -> Result<(&str, FieldTags, TypedField), Error> {
+    let parts: Vec<&str> = s.splitn(4, ':').collect();
+    match parts.as_slice() {
+        [descriptor, tags, field_type, value] => Ok((
+            descriptor,
+            FieldTags::from_str(tags)?,
+            TypedField::parse(field_type, value)?,
+        )),
+        _ => Err(Error::ParseFailure),
+    }
+}
+
+/// Operational state of a server, as reported by `show servers state`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ServerOpState {
+    Stopped,
+    Starting,
+    Running,
+    Stopping,
+}
+
+impl FromStr for ServerOpState {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "0" => Ok(ServerOpState::Stopped),
+            "1" => Ok(ServerOpState::Starting),
+            "2" => Ok(ServerOpState::Running),
+            "3" => Ok(ServerOpState::Stopping),
+            _ => Err(Error::ParseFailure),
+        }
+    }
+}
+
+/// Administrative state flags of a server, as reported by `show servers state`.
+///
+/// A server may be in maintenance or drain mode for several reasons at once, for example because
+/// it was forced into maintenance and also tracks a server in maintenance.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ServerAdminState(u8);
+
+impl ServerAdminState {
+    /// Forced into maintenance from the CLI or stats page.
+    pub const FORCED_MAINT: ServerAdminState = ServerAdminState(0x01);
+    /// In maintenance because a tracked server is in maintenance.
+    pub const INHERITED_MAINT: ServerAdminState = ServerAdminState(0x02);
+    /// In maintenance because of the configuration.
+    pub const CONFIG_MAINT: ServerAdminState = ServerAdminState(0x04);
+    /// Forced into drain mode from the CLI or stats page.
+    pub const FORCED_DRAIN: ServerAdminState = ServerAdminState(0x08);
+    /// In drain mode because a tracked server is draining.
+    pub const INHERITED_DRAIN: ServerAdminState = ServerAdminState(0x10);
+    /// In maintenance because its address could not be resolved.
+    pub const RESOLUTION_MAINT: ServerAdminState = ServerAdminState(0x20);
+    /// In maintenance because its FQDN was changed.
+    pub const HOSTNAME_MAINT: ServerAdminState = ServerAdminState(0x40);
+
+    const ANY_MAINT: u8 = 0x01 | 0x02 | 0x04 | 0x20 | 0x40;
+    const ANY_DRAIN: u8 = 0x08 | 0x10;
+
+    /// Check whether all flags in `other` are set.
+    pub fn contains(self, other: ServerAdminState) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether the server is in maintenance for any reason.
+    pub fn is_maint(self) -> bool {
+        self.0 & Self::ANY_MAINT != 0
+    }
+
+    /// Whether the server is draining for any reason.
+    pub fn is_drain(self) -> bool {
+        self.0 & Self::ANY_DRAIN != 0
+    }
+}
+
+impl FromStr for ServerAdminState {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(ServerAdminState(parse_field(s)?))
+    }
+}
+
+/// A single server of `show servers state` output.
+///
+/// Columns are matched by name, so rows from older and newer HAProxy versions can be read. Columns
+/// missing from the running version, or reported as `-`, are `None`. Columns that are not modeled
+/// here are kept in `extra`, keyed by their column name.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ServerStateRow {
+    pub be_id: i32,
+    pub be_name: String,
+    pub srv_id: i32,
+    pub srv_name: String,
+    pub srv_addr: Option<IpAddr>,
+    pub srv_op_state: Option<ServerOpState>,
+    pub srv_admin_state: Option<ServerAdminState>,
+    /// Current weight, as set by the user.
+    pub srv_uweight: Option<u32>,
+    /// Initial weight, from the configuration.
+    pub srv_iweight: Option<u32>,
+    /// Seconds since the last operational state change.
+    pub srv_time_since_last_change: Option<u64>,
+    pub srv_check_status: Option<u32>,
+    pub srv_check_result: Option<u32>,
+    pub srv_check_health: Option<u32>,
+    pub srv_check_state: Option<u32>,
+    pub srv_agent_state: Option<u32>,
+    pub bk_f_forced_id: Option<bool>,
+    pub srv_f_forced_id: Option<bool>,
+    pub srv_fqdn: Option<String>,
+    pub srv_port: Option<u16>,
+    pub srvrecord: Option<String>,
+    pub srv_use_ssl: Option<bool>,
+    pub srv_check_port: Option<u16>,
+    pub srv_check_addr: Option<String>,
+    pub srv_agent_addr: Option<String>,
+    pub srv_agent_port: Option<u16>,
+    /// Columns not modeled above.
+    pub extra: HashMap<String, String>,
+}
+
+impl ServerStateRow {
+    /// Build a row from `(column name, value)` pairs.
+    ///
+    /// Empty values and `-` are treated as missing. Unknown columns are stored in `extra`.
+    pub fn from_fields<'a, I: IntoIterator<Item = (&'a str, &'a str)>>(
+        fields: I,
+    ) -> Result<Self, Error> {
+        let mut row = ServerStateRow::default();
+        let mut has_ids = (false, false);
+
+        for (name, value) in fields {
+            if name.is_empty() || value.is_empty() || value == "-" {
+                continue;
+            }
+
+            match name {
+                "be_id" => {
+                    row.be_id = parse_field(value)?;
+                    has_ids.0 = true;
+                }
+                "be_name" => row.be_name = value.to_string(),
+                "srv_id" => {
+                    row.srv_id = parse_field(value)?;
+                    has_ids.1 = true;
+                }
+                "srv_name" => row.srv_name = value.to_string(),
+                "srv_addr" => row.srv_addr = Some(parse_field(value)?),
+                "srv_op_state" => row.srv_op_state = Some(ServerOpState::from_str(value)?),
+                "srv_admin_state" => row.srv_admin_state = Some(ServerAdminState::from_str(value)?),
+                "srv_uweight" => row.srv_uweight = Some(parse_field(value)?),
+                "srv_iweight" => row.srv_iweight = Some(parse_field(value)?),
+                "srv_time_since_last_change" => {
+                    row.srv_time_since_last_change = Some(parse_field(value)?)
+                }
+                "srv_check_status" => row.srv_check_status = Some(parse_field(value)?),
+                "srv_check_result" => row.srv_check_result = Some(parse_field(value)?),
+                "srv_check_health" => row.srv_check_health = Some(parse_field(value)?),
+                "srv_check_state" => row.srv_check_state = Some(parse_field(value)?),
+                "srv_agent_state" => row.srv_agent_state = Some(parse_field(value)?),
+                "bk_f_forced_id" => row.bk_f_forced_id = Some(parse_flag(value)?),
+                "srv_f_forced_id" => row.srv_f_forced_id = Some(parse_flag(value)?),
+                "srv_fqdn" => row.srv_fqdn = Some(value.to_string()),
+                "srv_port" => row.srv_port = Some(parse_field(value)?),
+                "srvrecord" => row.srvrecord = Some(value.to_string()),
+                "srv_use_ssl" => row.srv_use_ssl = Some(parse_flag(value)?),
+                "srv_check_port" => row.srv_check_port = Some(parse_field(value)?),
+                "srv_check_addr" => row.srv_check_addr = Some(value.to_string()),
+                "srv_agent_addr" => row.srv_agent_addr = Some(value.to_string()),
+                "srv_agent_port" => row.srv_agent_port = Some(parse_field(value)?),
+                name => {
+                    row.extra.insert(name.to_string(), value.to_string());
+                }
+            }
+        }
+
+        if !has_ids.0 || !has_ids.1 || row.be_name.is_empty() || row.srv_name.is_empty() {
+            return Err(Error::ParseFailure);
+        }
+
+        Ok(row)
+    }
+}
+
+/// Connection counters of a single server, as reported by `show servers conn`.
+///
+/// Columns are matched by name. Counters missing from the running HAProxy version are `None`, and
+/// columns that are not modeled here are kept in `extra`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ServerConnections {
+    pub be_name: String,
+    pub srv_name: String,
+    pub be_id: i32,
+    pub srv_id: i32,
+    pub addr: Option<IpAddr>,
+    pub port: Option<u16>,
+    /// Milliseconds before idle connections are closed.
+    pub purge_delay: Option<u32>,
+    pub used_cur: Option<u32>,
+    pub used_max: Option<u32>,
+    pub need_est: Option<u32>,
+    pub unsafe_nb: Option<u32>,
+    pub safe_nb: Option<u32>,
+    /// Limit on idle connections, or `None` when unlimited or not reported.
+    pub idle_lim: Option<u32>,
+    pub idle_cur: Option<u32>,
+    /// Idle connections held by each thread, indexed by thread number.
+    pub idle_per_thread: Vec<u32>,
+    /// Columns not modeled above.
+    pub extra: HashMap<String, String>,
+}
+
+impl ServerConnections {
+    /// Build a row from `(column name, value)` pairs.
+    ///
+    /// Values of repeated `idle_per_thr` columns are collected in order into `idle_per_thread`.
+    /// Empty values and `-` are treated as missing. Unknown columns are stored in `extra`.
+    pub fn from_fields<'a, I: IntoIterator<Item = (&'a str, &'a str)>>(
+        fields: I,
+    ) -> Result<Self, Error> {
+        let mut row = ServerConnections::default();
+
+        for (name, value) in fields {
+            if name.is_empty() || value.is_empty() || value == "-" {
+                continue;
+            }
+
+            match name {
+                "bkname/svname" => {
+                    let (be_name, srv_name) = value.split_once('/').ok_or(Error::ParseFailure)?;
+                    row.be_name = be_name.to_string();
+                    row.srv_name = srv_name.to_string();
+                }
+                "bkid/svid" => {
+                    let (be_id, srv_id) = value.split_once('/').ok_or(Error::ParseFailure)?;
+                    row.be_id = parse_field(be_id)?;
+                    row.srv_id = parse_field(srv_id)?;
+                }
+                "addr" => row.addr = Some(parse_field(value)?),
+                "port" => row.port = Some(parse_field(value)?),
+                "purge_delay" => row.purge_delay = Some(parse_field(value)?),
+                "used_cur" => row.used_cur = Some(parse_field(value)?),
+                "used_max" => row.used_max = Some(parse_field(value)?),
+                "need_est" => row.need_est = Some(parse_field(value)?),
+                "unsafe_nb" => row.unsafe_nb = Some(parse_field(value)?),
+                "safe_nb" => row.safe_nb = Some(parse_field(value)?),
+                // HAProxy reports -1 when the number of idle connections is not limited.
+                "idle_lim" => row.idle_lim = u32::try_from(parse_field::<i64>(value)?).ok(),
+                "idle_cur" => row.idle_cur = Some(parse_field(value)?),
+                "idle_per_thr" => row.idle_per_thread.push(parse_field(value)?),
+                name => {
+                    row.extra.insert(name.to_string(), value.to_string());
+                }
+            }
+        }
+
+        if row.be_name.is_empty() || row.srv_name.is_empty() {
+            return Err(Error::ParseFailure);
+        }
+
+        Ok(row)
+    }
+}
+
+/// A single session of `show sess` output.
+///
+/// HAProxy 2.6 and later report the front and back stream connectors as `scf` and `scb`; these are
+/// stored in `s0` and `s1` respectively so that the fields mean the same across versions. Values
+/// HAProxy reports as `<NONE>` are `None`. Fields not modeled here are kept in `extra`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Session {
+    /// The session's pointer, used to refer to it in `show sess <id>` and `shutdown session`.
+    pub ptr: u64,
+    pub proto: Option<String>,
+    /// The client address.
+    pub src: Option<String>,
+    /// The frontend name.
+    pub fe: Option<String>,
+    /// The backend name.
+    pub be: Option<String>,
+    /// The server name.
+    pub srv: Option<String>,
+    /// The session's termination state flags.
+    pub ts: Option<u8>,
+    pub age: Option<Duration>,
+    pub calls: Option<u64>,
+    /// State of the request channel.
+    pub rq: Option<String>,
+    /// State of the response channel.
+    pub rp: Option<String>,
+    /// State of the client-side connection.
+    pub s0: Option<String>,
+    /// State of the server-side connection.
+    pub s1: Option<String>,
+    /// Fields not modeled above.
+    pub extra: HashMap<String, String>,
+}
+
+impl FromStr for Session {
+    type Err = Error;
+
+    /// Parse a line such as `0x55d1c3e6a5f0: proto=tcpv4 src=127.0.0.1:54321 fe=http be=servers
+    /// srv=server1 ts=08 age=3s calls=2 rq[f=848000h,i=0] rp[f=80048000h,i=0] s0=[8,200h,fd=12]
+    /// s1=[8,10h,fd=13] exp=`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = s.split_whitespace();
+        let ptr = tokens
+            .next()
+            .and_then(|ptr| ptr.strip_suffix(':'))
+            .and_then(|ptr| ptr.strip_prefix("0x"))
+            .ok_or(Error::ParseFailure)?;
+
+        let mut session = Session {
+            ptr: u64::from_str_radix(ptr, 16)?,
+            ..Default::default()
+        };
+
+        for token in tokens {
+            // Fields are either `name=value`, `name=[state]` or `name[state]`.
+            let split = token.find(['=', '[']).ok_or(Error::ParseFailure)?;
+            let (name, value) = token.split_at(split);
+            let value = value.strip_prefix('=').unwrap_or(value);
+            let value = value
+                .strip_prefix('[')
+                .and_then(|value| value.strip_suffix(']'))
+                .unwrap_or(value);
+            if value.is_empty() || value.eq_ignore_ascii_case("<none>") {
+                continue;
+            }
+
+            match name {
+                "proto" => session.proto = Some(value.to_string()),
+                "src" => session.src = Some(value.to_string()),
+                "fe" => session.fe = Some(value.to_string()),
+                "be" => session.be = Some(value.to_string()),
+                "srv" => session.srv = Some(value.to_string()),
+                "ts" => session.ts = Some(u8::from_str_radix(value, 16)?),
+                "age" => session.age = Some(parse_human_duration(value)?),
+                "calls" => session.calls = Some(parse_field(value)?),
+                "rq" => session.rq = Some(value.to_string()),
+                "rp" => session.rp = Some(value.to_string()),
+                "s0" | "scf" => session.s0 = Some(value.to_string()),
+                "s1" | "scb" => session.s1 = Some(value.to_string()),
+                name => {
+                    session.extra.insert(name.to_string(), value.to_string());
+                }
+            }
+        }
+
+        Ok(session)
+    }
+}
+
+/// Parse a duration in HAProxy's human readable format, such as `1d2h` or `3m15s`.
+fn parse_human_duration(s: &str) -> Result<Duration, Error> {
+    if s.is_empty() {
+        return Err(Error::ParseFailure);
+    }
+
+    let mut seconds = 0;
+    let mut rest = s;
+
+    while !rest.is_empty() {
+        let unit_at = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or(Error::ParseFailure)?;
+        let (value, unit) = rest.split_at(unit_at);
+        let value: u64 = parse_field(value)?;
+        let mut unit = unit.chars();
+        seconds += value
+            * match unit.next() {
+                Some('d') => 86400,
+                Some('h') => 3600,
+                Some('m') => 60,
+                Some('s') => 1,
+                _ => return Err(Error::ParseFailure),
+            };
+        rest = unit.as_str();
+    }
+
+    Ok(Duration::from_secs(seconds))
+}
+
+/// A stick table, as reported by `show table`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StickTable {
+    pub name: String,
+    pub table_type: StickTableType,
+    /// The maximum number of entries.
+    pub size: u64,
+    /// The current number of entries.
+    pub used: u64,
+}
+
+impl FromStr for StickTable {
+    type Err = Error;
+
+    /// Parse a line such as `# table: http-in, type: ip, size:1048576, used:2`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s
+            .strip_prefix("# table: ")
+            .ok_or(Error::ParseFailure)?
+            .split(", ");
+        let name = parts.next().ok_or(Error::ParseFailure)?;
+
+        let (mut table_type, mut size, mut used) = (None, None, None);
+        for part in parts {
+            let (field, value) = part.split_once(':').ok_or(Error::ParseFailure)?;
+            match field {
+                "type" => table_type = Some(StickTableType::from_str(value.trim())?),
+                "size" => size = Some(parse_field(value.trim())?),
+                "used" => used = Some(parse_field(value.trim())?),
+                _ => {}
+            }
+        }
+
+        Ok(StickTable {
+            name: name.to_string(),
+            table_type: table_type.ok_or(Error::ParseFailure)?,
+            size: size.ok_or(Error::ParseFailure)?,
+            used: used.ok_or(Error::ParseFailure)?,
+        })
+    }
+}
+
+/// The value of a stick table data column.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum StickTableData {
+    Unsigned(u64),
+    Signed(i64),
+    /// An event rate measured over `period`, such as `conn_rate(10000)`.
+    Rate {
+        period: Duration,
+        value: u64,
+    },
+    Text(String),
+}
+
+impl StickTableData {
+    /// The value as an integer, if it is a number or a rate.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            StickTableData::Unsigned(value) | StickTableData::Rate { value, .. } => Some(*value),
+            StickTableData::Signed(value) => u64::try_from(*value).ok(),
+            StickTableData::Text(_) => None,
+        }
+    }
+}
+
+/// An entry of a stick table, as reported by `show table <name>`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StickTableEntry {
+    /// The entry's pointer within HAProxy.
+    pub ptr: u64,
+    pub key: StickTableKey,
+    /// The number of sessions currently tracking the entry.
+    pub use_count: u32,
+    /// Time until the entry expires.
+    pub exp: Option<Duration>,
+    /// Data columns keyed by name without the period, such as `gpc0` or `conn_rate`.
+    pub data: HashMap<String, StickTableData>,
+}
+
+impl StickTableEntry {
+    /// Parse a line such as `0x55d1c3e6a5f0: key=127.0.0.1 use=0 exp=86399000 gpc0=1
+    /// conn_rate(10000)=3` from a table whose keys are of type `key_type`.
+    pub fn parse(key_type: StickTableType, s: &str) -> Result<Self, Error> {
+        let mut tokens = s.split_whitespace();
+        let ptr = tokens
+            .next()
+            .and_then(|ptr| ptr.strip_suffix(':'))
+            .and_then(|ptr| ptr.strip_prefix("0x"))
+            .ok_or(Error::ParseFailure)?;
+        let ptr = u64::from_str_radix(ptr, 16)?;
+
+        let (mut key, mut use_count, mut exp) = (None, None, None);
+        let mut data = HashMap::new();
+        for token in tokens {
+            let (name, value) = token.split_once('=').ok_or(Error::ParseFailure)?;
+            match name {
+                "key" => key = Some(StickTableKey::parse(key_type, value)?),
+                "use" => use_count = Some(parse_field(value)?),
+                "exp" => exp = Some(Duration::from_millis(parse_field(value)?)),
+                // The shard an entry belongs to is an implementation detail of HAProxy 3.0+.
+                "shard" => {}
+                name => {
+                    let (name, data_value) = parse_table_data(name, value)?;
+                    data.insert(name.to_string(), data_value);
+                }
+            }
+        }
+
+        Ok(StickTableEntry {
+            ptr,
+            key: key.ok_or(Error::ParseFailure)?,
+            use_count: use_count.ok_or(Error::ParseFailure)?,
+            exp,
+            data,
+        })
+    }
+}
+
+/// Parse a data column, splitting the period off rate columns such as `conn_rate(10000)`.
+fn parse_table_data<'a>(name: &'a str, value: &str) -> Result<(&'a str, StickTableData), Error> {
+    if let Some((name, period)) = name.split_once('(') {
+        let period = period.strip_suffix(')').ok_or(Error::ParseFailure)?;
+        return Ok((
+            name,
+            StickTableData::Rate {
+                period: Duration::from_millis(parse_field(period)?),
+                value: parse_field(value)?,
+            },
+        ));
+    }
+
+    let value = if let Ok(value) = u64::from_str(value) {
+        StickTableData::Unsigned(value)
+    } else if let Ok(value) = i64::from_str(value) {
+        StickTableData::Signed(value)
+    } else {
+        StickTableData::Text(value.to_string())
+    };
+    Ok((name, value))
+}
+
+/// Whether a command changed the state of an object.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StateChange {
+    /// The object changed to the requested state.
+    Applied,
+    /// The object was already in the requested state.
+    Unchanged,
+}
+
+/// The changes HAProxy reported after `set server <backend>/<server> addr`.
+///
+/// Each pair of fields is only set when the corresponding value changed.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AddrChange {
+    pub old_addr: Option<IpAddr>,
+    pub new_addr: Option<IpAddr>,
+    pub old_port: Option<u16>,
+    pub new_port: Option<u16>,
+}
+
+impl AddrChange {
+    /// Whether HAProxy changed the address or port.
+    pub fn is_changed(&self) -> bool {
+        self.new_addr.is_some() || self.new_port.is_some()
+    }
+}
+
+impl FromStr for AddrChange {
+    type Err = Error;
+
+    /// Parse a message such as `IP changed from '10.0.0.1' to '10.0.0.2', no need to change the
+    /// port by 'stats socket command'`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut change = AddrChange::default();
+
+        if let Some((old, new)) = changed_from_to(s, "IP changed from ") {
+            change.old_addr = Some(parse_field(old)?);
+            change.new_addr = Some(parse_field(new)?);
+        } else if !s.starts_with("no need to change the addr") {
+            return Err(Error::ParseFailure);
+        }
+
+        if let Some((old, new)) = changed_from_to(s, "port changed from ") {
+            // HAProxy prefixes ports mapped relative to the client port with '+'.
+            change.old_port = Some(parse_field(old.trim_start_matches('+'))?);
+            change.new_port = Some(parse_field(new.trim_start_matches('+'))?);
+        }
+
+        Ok(change)
+    }
+}
+
+/// Find `<prefix>'<old>' to '<new>'` in `s`, returning `old` and `new`.
+fn changed_from_to<'a>(s: &'a str, prefix: &str) -> Option<(&'a str, &'a str)> {
+    let rest = &s[s.find(prefix)? + prefix.len()..];
+    let rest = rest.strip_prefix('\'')?;
+    let (old, rest) = rest.split_at(rest.find('\'')?);
+    let rest = rest.strip_prefix("' to '")?;
+    let (new, _) = rest.split_at(rest.find('\'')?);
+    Some((old, new))
+}
+
+fn parse_field<V: FromStr>(value: &str) -> Result<V, Error> {
+    V::from_str(value).map_err(|_| Error::ParseFailure)
+}
+
+/// Parse a `0` or `1` flag.
+fn parse_flag(value: &str) -> Result<bool, Error> {
+    match value {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err(Error::ParseFailure),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,6 +1552,71 @@ mod tests {
         Acl::from_str("1 ()").expect_err("Parsed an invalid ACL without error");
     }
 
+    #[test]
+    fn acl_match_from_str() {
+        assert_eq!(
+            AclMatch::from_str(
+                "type=ip, case=sensitive, match=yes, idx=tree, pattern=\"127.0.0.0/8\""
+            )
+            .unwrap(),
+            AclMatch {
+                match_type: "ip".into(),
+                case_sensitive: true,
+                matched: true,
+                pattern: Some("127.0.0.0/8".into()),
+            }
+        );
+        assert_eq!(
+            AclMatch::from_str("type=beg, case=insensitive, match=no").unwrap(),
+            AclMatch {
+                match_type: "beg".into(),
+                case_sensitive: false,
+                matched: false,
+                pattern: None,
+            }
+        );
+        AclMatch::from_str("match=no").expect_err("Parsed an ACL match without a type");
+    }
+
+    #[test]
+    fn map_from_bytes() {
+        assert_eq!(
+            Map::from_str("2 (/etc/haproxy/hosts.map) pattern loaded from file '/etc/haproxy/hosts.map' used by map at file '/etc/haproxy/haproxy.cfg' line 26").unwrap(),
+            Map {
+                id: 2,
+                reference: Some("/etc/haproxy/hosts.map".into()),
+                description: "pattern loaded from file '/etc/haproxy/hosts.map' used by map at file '/etc/haproxy/haproxy.cfg' line 26".into()
+            }
+        );
+        Map::from_str("2 ()").expect_err("Parsed an invalid map without error");
+    }
+
+    #[test]
+    fn map_match_from_str() {
+        assert_eq!(
+            MapMatch::from_str("type=str, case=sensitive, found=yes, idx=tree, key=\"example.com\", value=\"server1\", type=\"str\"").unwrap(),
+            MapMatch {
+                match_type: "str".into(),
+                case_sensitive: true,
+                found: true,
+                key: Some("example.com".into()),
+                value: Some("server1".into()),
+            }
+        );
+        assert_eq!(
+            MapMatch::from_str("type=str, case=insensitive, found=no").unwrap(),
+            MapMatch {
+                match_type: "str".into(),
+                case_sensitive: false,
+                found: false,
+                key: None,
+                value: None,
+            }
+        );
+        MapMatch::from_str("Unknown map identifier. Please use #<id> or <file>.")
+            .expect_err("Parsed an error message as a map match");
+    }
+
     #[test]
     fn level_from_bytes() {
         assert_eq!(Level::from_str("admin").unwrap(), Level::Admin);
@@ -228,4 +1676,380 @@ mod tests {
             CliSocketProcesses::List(vec![0, 1, 2]),
         );
     }
+
+    #[test]
+    fn stat_type_from_str() {
+        assert_eq!(StatType::from_str("0").unwrap(), StatType::Frontend);
+        assert_eq!(StatType::from_str("1").unwrap(), StatType::Backend);
+        assert_eq!(StatType::from_str("2").unwrap(), StatType::Server);
+        assert_eq!(StatType::from_str("3").unwrap(), StatType::Listener);
+        StatType::from_str("4").expect_err("Parsed invalid stat type");
+    }
+
+    #[test]
+    fn stat_row_from_fields() {
+        let row = StatRow::from_fields(vec![
+            ("pxname", "servers"),
+            ("svname", "server1"),
+            ("scur", "3"),
+            ("status", "UP"),
+            ("weight", "1"),
+            ("type", "2"),
+            ("check_status", "L4OK"),
+            ("lastsess", "-1"),
+            ("qlimit", ""),
+            ("new_column", "42"),
+        ])
+        .unwrap();
+
+        assert_eq!(row.pxname, "servers");
+        assert_eq!(row.svname, "server1");
+        assert_eq!(row.stat_type, Some(StatType::Server));
+        assert_eq!(row.scur, Some(3));
+        assert_eq!(row.status, Some("UP".into()));
+        assert_eq!(row.weight, Some(1));
+        assert_eq!(row.check_status, Some("L4OK".into()));
+        assert_eq!(row.lastsess, Some(-1));
+        assert_eq!(row.qlimit, None);
+        assert_eq!(row.extra.get("new_column"), Some(&"42".to_string()));
+
+        StatRow::from_fields(vec![("pxname", "servers"), ("scur", "abc")])
+            .expect_err("Parsed invalid stat row without error");
+        StatRow::from_fields(vec![("scur", "1")])
+            .expect_err("Parsed stat row without a proxy name");
+    }
+
+    #[test]
+    fn info_from_fields() {
+        let info = Info::from_fields(vec![
+            ("Name", "HAProxy"),
+            ("Version", "2.2.4"),
+            ("Nbthread", "4"),
+            ("Uptime", "0d 0h00m10s"),
+            ("Uptime_sec", "10"),
+            ("Idle_pct", "100"),
+            ("Stopping", "0"),
+            ("description", ""),
+        ])
+        .unwrap();
+
+        assert_eq!(info.name, Some("HAProxy".into()));
+        assert_eq!(info.version, Some("2.2.4".into()));
+        assert_eq!(info.nbthread, Some(4));
+        assert_eq!(info.uptime_sec, Some(10));
+        assert_eq!(info.idle_pct, Some(100));
+        assert_eq!(info.stopping, Some(false));
+        assert_eq!(info.description, None);
+        assert_eq!(info.extra.get("Uptime"), Some(&"0d 0h00m10s".to_string()));
+
+        Info::from_fields(vec![("Pid", "abc")]).expect_err("Parsed invalid info without error");
+    }
+
+    #[test]
+    fn field_tags_from_str() {
+        assert_eq!(
+            FieldOrigin::from_str("Metric").unwrap(),
+            FieldOrigin::Metric
+        );
+        assert_eq!(
+            FieldNature::from_str("Counter").unwrap(),
+            FieldNature::Counter
+        );
+        assert_eq!(FieldNature::from_str("Gauge").unwrap(), FieldNature::Gauge);
+        assert_eq!(
+            FieldScope::from_str("Process").unwrap(),
+            FieldScope::Process
+        );
+        FieldOrigin::from_str("metric").expect_err("Parsed invalid field origin");
+        FieldNature::from_str("").expect_err("Parsed invalid field nature");
+        FieldScope::from_str("Global").expect_err("Parsed invalid field scope");
+    }
+
+    #[test]
+    fn field_tags_from_codes() {
+        assert_eq!(
+            FieldTags::from_str("MGP").unwrap(),
+            FieldTags {
+                origin: FieldOrigin::Metric,
+                nature: FieldNature::Gauge,
+                scope: FieldScope::Process,
+            }
+        );
+        assert_eq!(
+            FieldTags::from_str("KNS").unwrap(),
+            FieldTags {
+                origin: FieldOrigin::Key,
+                nature: FieldNature::Name,
+                scope: FieldScope::Service,
+            }
+        );
+        FieldTags::from_str("MG").expect_err("Parsed truncated field tags");
+        FieldTags::from_str("XGP").expect_err("Parsed invalid field origin");
+    }
+
+    #[test]
+    fn typed_field_parse() {
+        assert_eq!(TypedField::parse("s32", "-1").unwrap(), TypedField::S32(-1));
+        assert_eq!(TypedField::parse("u64", "12").unwrap(), TypedField::U64(12));
+        assert_eq!(
+            TypedField::parse("flt", "0.5").unwrap(),
+            TypedField::Float(0.5)
+        );
+        assert_eq!(
+            TypedField::parse("str", "a:b").unwrap(),
+            TypedField::Str("a:b".into())
+        );
+        TypedField::parse("u32", "-1").expect_err("Parsed negative unsigned field");
+        TypedField::parse("dur", "1").expect_err("Parsed unknown field type");
+    }
+
+    #[test]
+    fn typed_stat_from_str() {
+        assert_eq!(
+            TypedStat::from_str("L.2.1.73.addr.1:CGS:str:0.0.0.0:8001").unwrap(),
+            TypedStat {
+                stat_type: StatType::Listener,
+                proxy_id: 2,
+                object_id: 1,
+                position: 73,
+                name: "addr".into(),
+                process: 1,
+                tags: FieldTags {
+                    origin: FieldOrigin::Config,
+                    nature: FieldNature::Gauge,
+                    scope: FieldScope::Service,
+                },
+                value: TypedField::Str("0.0.0.0:8001".into()),
+            }
+        );
+        TypedStat::from_str("X.2.1.73.addr.1:CGS:str:x").expect_err("Parsed invalid object type");
+        TypedStat::from_str("F.2.0.scur.1:MGP:u32:0").expect_err("Parsed truncated descriptor");
+    }
+
+    #[test]
+    fn typed_info_from_str() {
+        assert_eq!(
+            TypedInfo::from_str("0.Name.1:POS:str:HAProxy").unwrap(),
+            TypedInfo {
+                position: 0,
+                name: "Name".into(),
+                process: 1,
+                tags: FieldTags {
+                    origin: FieldOrigin::Product,
+                    nature: FieldNature::Output,
+                    scope: FieldScope::Service,
+                },
+                value: TypedField::Str("HAProxy".into()),
+            }
+        );
+        TypedInfo::from_str("0.Name.1:POS:str").expect_err("Parsed line without a value");
+    }
+
+    #[test]
+    fn addr_change_from_str() {
+        assert_eq!(
+            AddrChange::from_str(
+                "IP changed from '127.0.0.1' to '127.0.0.2', no need to change the port by 'stats socket command'"
+            )
+            .unwrap(),
+            AddrChange {
+                old_addr: Some("127.0.0.1".parse().unwrap()),
+                new_addr: Some("127.0.0.2".parse().unwrap()),
+                old_port: None,
+                new_port: None,
+            }
+        );
+        assert_eq!(
+            AddrChange::from_str(
+                "no need to change the addr, port changed from '8000' to '8001' by 'stats socket command'"
+            )
+            .unwrap(),
+            AddrChange {
+                old_addr: None,
+                new_addr: None,
+                old_port: Some(8000),
+                new_port: Some(8001),
+            }
+        );
+        assert!(
+            !AddrChange::from_str("no need to change the addr by 'stats socket command'")
+                .unwrap()
+                .is_changed()
+        );
+        AddrChange::from_str("Invalid addr 'abc'").expect_err("Parsed an error message");
+    }
+
+    #[test]
+    fn server_admin_state_flags() {
+        let state = ServerAdminState::from_str("9").unwrap();
+        assert!(state.contains(ServerAdminState::FORCED_MAINT));
+        assert!(state.contains(ServerAdminState::FORCED_DRAIN));
+        assert!(state.is_maint());
+        assert!(state.is_drain());
+
+        let state = ServerAdminState::from_str("0").unwrap();
+        assert!(!state.is_maint());
+        assert!(!state.is_drain());
+    }
+
+    #[test]
+    fn server_state_row_from_fields() {
+        let row = ServerStateRow::from_fields(vec![
+            ("be_id", "3"),
+            ("be_name", "servers"),
+            ("srv_id", "1"),
+            ("srv_name", "server1"),
+            ("srv_addr", "127.0.0.1"),
+            ("srv_op_state", "2"),
+            ("srv_admin_state", "0"),
+            ("srv_uweight", "1"),
+            ("srv_iweight", "1"),
+            ("srv_fqdn", "-"),
+            ("srv_port", "8000"),
+            ("srv_use_ssl", "0"),
+            ("srv_future_column", "7"),
+        ])
+        .unwrap();
+
+        assert_eq!(row.be_id, 3);
+        assert_eq!(row.srv_name, "server1");
+        assert_eq!(row.srv_addr, Some("127.0.0.1".parse().unwrap()));
+        assert_eq!(row.srv_op_state, Some(ServerOpState::Running));
+        assert_eq!(row.srv_admin_state, Some(ServerAdminState::default()));
+        assert_eq!(row.srv_fqdn, None);
+        assert_eq!(row.srv_port, Some(8000));
+        assert_eq!(row.srv_use_ssl, Some(false));
+        assert_eq!(row.srv_check_port, None);
+        assert_eq!(
+            row.extra.get("srv_future_column").map(String::as_str),
+            Some("7")
+        );
+
+        ServerStateRow::from_fields(vec![("be_id", "3"), ("be_name", "servers")])
+            .expect_err("Parsed a row without a server");
+    }
+
+    #[test]
+    fn server_connections_from_fields() {
+        let row = ServerConnections::from_fields(vec![
+            ("bkname/svname", "servers/server1"),
+            ("bkid/svid", "3/1"),
+            ("addr", "127.0.0.1"),
+            ("port", "8000"),
+            ("", "-"),
+            ("purge_delay", "5000"),
+            ("used_cur", "2"),
+            ("idle_lim", "-1"),
+            ("idle_cur", "3"),
+            ("idle_per_thr", "1"),
+            ("idle_per_thr", "2"),
+        ])
+        .unwrap();
+
+        assert_eq!(row.be_name, "servers");
+        assert_eq!(row.srv_name, "server1");
+        assert_eq!((row.be_id, row.srv_id), (3, 1));
+        assert_eq!(row.port, Some(8000));
+        assert_eq!(row.used_cur, Some(2));
+        assert_eq!(row.idle_lim, None);
+        assert_eq!(row.idle_per_thread, vec![1, 2]);
+
+        ServerConnections::from_fields(vec![("bkname/svname", "servers")])
+            .expect_err("Parsed a name without a server");
+    }
+
+    #[test]
+    fn session_from_str() {
+        let session = Session::from_str(
+            "0x55d1c3e6a5f0: proto=tcpv4 src=127.0.0.1:54321 fe=http be=servers srv=server1 ts=08 epoch=0 age=1m5s calls=2 rq[f=848000h,i=0,an=00h,rx=,wx=,ax=] rp[f=80048000h,i=0,an=00h,rx=,wx=,ax=] scf=[8,200h,fd=12] scb=[8,10h,fd=13] exp=",
+        )
+        .unwrap();
+
+        assert_eq!(session.ptr, 0x55d1c3e6a5f0);
+        assert_eq!(session.proto.as_deref(), Some("tcpv4"));
+        assert_eq!(session.src.as_deref(), Some("127.0.0.1:54321"));
+        assert_eq!(session.srv.as_deref(), Some("server1"));
+        assert_eq!(session.ts, Some(8));
+        assert_eq!(session.age, Some(Duration::from_secs(65)));
+        assert_eq!(session.calls, Some(2));
+        assert_eq!(
+            session.rq.as_deref(),
+            Some("f=848000h,i=0,an=00h,rx=,wx=,ax=")
+        );
+        assert_eq!(session.s0.as_deref(), Some("8,200h,fd=12"));
+        assert_eq!(session.s1.as_deref(), Some("8,10h,fd=13"));
+        assert_eq!(session.extra.get("epoch").map(String::as_str), Some("0"));
+        assert!(!session.extra.contains_key("exp"));
+
+        let session = Session::from_str(
+            "0x1: proto=unix_stream src=unix:1 fe=GLOBAL be=<NONE> srv=<none> ts=00 age=0s calls=1",
+        )
+        .unwrap();
+        assert_eq!(session.be, None);
+        assert_eq!(session.srv, None);
+
+        Session::from_str("Session not found.").expect_err("Parsed an error message");
+    }
+
+    #[test]
+    fn parse_human_duration_units() {
+        assert_eq!(
+            parse_human_duration("1d2h").unwrap(),
+            Duration::from_secs(93600)
+        );
+        assert_eq!(
+            parse_human_duration("12s").unwrap(),
+            Duration::from_secs(12)
+        );
+        parse_human_duration("").expect_err("Parsed an empty duration");
+        parse_human_duration("5").expect_err("Parsed a duration without a unit");
+        parse_human_duration("5w").expect_err("Parsed an unknown unit");
+        parse_human_duration("5é").expect_err("Parsed a non-ASCII unit");
+    }
+
+    #[test]
+    fn stick_table_from_str() {
+        assert_eq!(
+            StickTable::from_str("# table: http-in, type: ip, size:1048576, used:2").unwrap(),
+            StickTable {
+                name: "http-in".into(),
+                table_type: StickTableType::Ip,
+                size: 1048576,
+                used: 2,
+            }
+        );
+        StickTable::from_str("# table: http-in, type: ip").expect_err("Parsed a partial table");
+    }
+
+    #[test]
+    fn stick_table_entry_parse() {
+        let entry = StickTableEntry::parse(
+            StickTableType::Ip,
+            "0x55d1c3e6a5f0: key=127.0.0.1 use=0 exp=86399000 shard=0 server_id=-1 gpc0=1 conn_rate(10000)=3 server_name=server1",
+        )
+        .unwrap();
+
+        assert_eq!(entry.ptr, 0x55d1c3e6a5f0);
+        assert_eq!(entry.key, StickTableKey::Ip("127.0.0.1".parse().unwrap()));
+        assert_eq!(entry.use_count, 0);
+        assert_eq!(entry.exp, Some(Duration::from_secs(86399)));
+        assert_eq!(entry.data["gpc0"], StickTableData::Unsigned(1));
+        assert_eq!(entry.data["server_id"], StickTableData::Signed(-1));
+        assert_eq!(
+            entry.data["conn_rate"],
+            StickTableData::Rate {
+                period: Duration::from_secs(10),
+                value: 3
+            }
+        );
+        assert_eq!(entry.data["conn_rate"].as_u64(), Some(3));
+        assert_eq!(
+            entry.data["server_name"],
+            StickTableData::Text("server1".into())
+        );
+        assert!(!entry.data.contains_key("shard"));
+
+        StickTableEntry::parse(StickTableType::Integer, "0x1: key=abc use=0 exp=0")
+            .expect_err("Parsed a string key in an integer table");
+    }
 }
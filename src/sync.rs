@@ -0,0 +1,155 @@
+//! Reconcile the contents of ACLs with a desired state.
+//!
+//! Rather than clearing and refilling an ACL, [`sync_acl`] compares the current entries with the
+//! desired entries and only adds and removes the difference. Running it repeatedly with the same
+//! desired state makes no further changes.
+
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::io::{Read, Write};
+use std::str::FromStr;
+
+use crate::connection::{Connection, ConnectionBuilder};
+use crate::errors::Error;
+use crate::models::{AclEntry, AclId};
+use crate::requests::EntryRef;
+
+/// The changes made by [`sync_acl`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SyncReport<E> {
+    /// Values added to the ACL.
+    pub added: Vec<E>,
+    /// Entries removed from the ACL, including duplicates of desired values.
+    pub removed: Vec<AclEntry<E>>,
+}
+
+impl<E> SyncReport<E> {
+    /// Whether the ACL already matched the desired state.
+    pub fn is_unchanged(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Update an ACL so that it contains exactly the values in `desired`.
+///
+/// Values are compared after parsing the current entries into `E`, so `E` should parse and format
+/// values the same way HAProxy does; for example, use `IpAddr` rather than `String` for IP
+/// addresses. Missing values are added before extra entries are removed, and entries are removed
+/// by element pointer so that only the exact entries read are affected.
+///
+/// Each change is a separate command, so other clients may observe the ACL part way through
+/// synchronization. Use [`crate::transaction::AclTransaction`] to replace an ACL atomically.
+///
+/// # Examples
+/// ```no_run
+/// use std::collections::HashSet;
+/// use std::net::IpAddr;
+/// use haptik::UnixSocketBuilder;
+/// use haptik::models::AclId;
+/// use haptik::sync::sync_acl;
+///
+/// let socket_builder = UnixSocketBuilder::default();
+/// let desired: HashSet<IpAddr> = vec!["127.0.0.1".parse().unwrap()].into_iter().collect();
+/// let report = sync_acl(&socket_builder, AclId::Id(0), &desired).expect("Failed to sync ACL");
+/// println!("added {:?}, removed {:?}", report.added, report.removed);
+/// ```
+pub fn sync_acl<B, T, E>(
+    builder: &B,
+    id: AclId,
+    desired: &HashSet<E>,
+) -> Result<SyncReport<E>, Error>
+where
+    B: ConnectionBuilder<Connection = Connection<T>>,
+    T: Read + Write,
+    E: FromStr + ToString + Eq + Hash + Clone,
+{
+    let current = builder.connect()?.acl_data::<E>(id.clone())?;
+    let report = diff(current, desired);
+
+    for value in report.added.iter() {
+        builder.connect()?.acl_add(id.clone(), value.to_string())?;
+    }
+    for entry in report.removed.iter() {
+        builder
+            .connect()?
+            .acl_del(id.clone(), EntryRef::Pointer(entry.id))?;
+    }
+
+    Ok(report)
+}
+
+/// Compute the changes needed to turn `current` into `desired`.
+fn diff<E: Eq + Hash + Clone>(current: Vec<AclEntry<E>>, desired: &HashSet<E>) -> SyncReport<E> {
+    let mut seen = HashSet::with_capacity(current.len());
+    let mut removed = Vec::new();
+
+    for entry in current {
+        if desired.contains(&entry.value) && !seen.contains(&entry.value) {
+            seen.insert(entry.value);
+        } else {
+            removed.push(entry);
+        }
+    }
+
+    let added = desired
+        .iter()
+        .filter(|value| !seen.contains(*value))
+        .cloned()
+        .collect();
+
+    SyncReport { added, removed }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u64, value: &str) -> AclEntry<String> {
+        AclEntry {
+            id,
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn diff_adds_and_removes() {
+        let desired: HashSet<String> = vec!["a".to_string(), "b".to_string()].into_iter().collect();
+        let report = diff(vec![entry(1, "a"), entry(2, "c")], &desired);
+
+        assert_eq!(report.added, vec!["b".to_string()]);
+        assert_eq!(report.removed, vec![entry(2, "c")]);
+    }
+
+    #[test]
+    fn diff_removes_duplicates() {
+        let desired: HashSet<String> = vec!["a".to_string()].into_iter().collect();
+        let report = diff(vec![entry(1, "a"), entry(2, "a")], &desired);
+
+        assert!(report.added.is_empty());
+        assert_eq!(report.removed, vec![entry(2, "a")]);
+    }
+
+    #[test]
+    fn sync_adds_values_with_spaces() {
+        let builder = crate::mock::MockBuilder::new(&[b"0x1 a b\n\n", b"\n"]);
+        let desired: HashSet<String> = vec!["a b".to_string(), "c d".to_string()]
+            .into_iter()
+            .collect();
+
+        let report = sync_acl(&builder, AclId::Id(0), &desired).unwrap();
+        assert_eq!(report.added, vec!["c d".to_string()]);
+        assert!(report.removed.is_empty());
+        assert_eq!(
+            builder.output.borrow().as_slice(),
+            &b"show acl #0\nadd acl #0 c\\ d\n"[..]
+        );
+    }
+
+    #[test]
+    fn diff_unchanged() {
+        let desired: HashSet<String> = vec!["a".to_string(), "b".to_string()].into_iter().collect();
+        let report = diff(vec![entry(1, "a"), entry(2, "b")], &desired);
+
+        assert!(report.is_unchanged());
+    }
+}
@@ -0,0 +1,245 @@
+//! Atomically replace the contents of ACLs and maps.
+//!
+//! HAProxy 2.4 and later can prepare a new, empty version of an ACL or map, fill it, and then
+//! commit it, replacing the previous contents in a single step. Until the version is committed,
+//! lookups continue to use the previous contents, so there is never a moment where the ACL or map
+//! is partially populated.
+//!
+//! Each step of a transaction is a separate command, so transactions hold a [`ConnectionBuilder`]
+//! and open a connection for each step.
+
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+
+use crate::connection::{Connection, ConnectionBuilder};
+use crate::errors::Error;
+use crate::models::{AclId, MapId};
+
+/// A prepared version of an ACL which replaces the current contents when committed.
+///
+/// If the transaction is dropped without calling [`AclTransaction::commit`], the prepared version
+/// is cleared and the ACL is left unchanged.
+///
+/// # Examples
+/// ```no_run
+/// use std::net::Ipv4Addr;
+/// use haptik::UnixSocketBuilder;
+/// use haptik::models::AclId;
+/// use haptik::transaction::AclTransaction;
+///
+/// let socket_builder = UnixSocketBuilder::default();
+/// let mut transaction =
+///     AclTransaction::begin(&socket_builder, AclId::Id(0)).expect("Failed to prepare ACL");
+/// transaction.add(Ipv4Addr::new(127, 0, 0, 1)).expect("Failed to add entry");
+/// transaction.add(Ipv4Addr::new(127, 0, 0, 2)).expect("Failed to add entry");
+/// transaction.commit().expect("Failed to commit ACL");
+/// ```
+pub struct AclTransaction<'a, B, T>
+where
+    B: ConnectionBuilder<Connection = Connection<T>>,
+    T: Read + Write,
+{
+    builder: &'a B,
+    id: AclId,
+    version: u32,
+    finished: bool,
+    transport: PhantomData<T>,
+}
+
+impl<'a, B, T> AclTransaction<'a, B, T>
+where
+    B: ConnectionBuilder<Connection = Connection<T>>,
+    T: Read + Write,
+{
+    /// Prepare a new, empty version of the ACL.
+    pub fn begin(builder: &'a B, id: AclId) -> Result<Self, Error> {
+        let version = builder.connect()?.acl_prepare(id.clone())?;
+
+        Ok(Self {
+            builder,
+            id,
+            version,
+            finished: false,
+            transport: PhantomData,
+        })
+    }
+
+    /// The version number HAProxy assigned to this transaction.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Add an entry to the prepared version.
+    pub fn add<E: ToString>(&mut self, value: E) -> Result<(), Error> {
+        self.builder
+            .connect()?
+            .acl_add_version(self.id.clone(), self.version, value)
+    }
+
+    /// Replace the contents of the ACL with the prepared version.
+    ///
+    /// If the commit fails, the prepared version is cleared when the transaction is dropped.
+    pub fn commit(mut self) -> Result<(), Error> {
+        self.builder
+            .connect()?
+            .acl_commit(self.id.clone(), self.version)?;
+        self.finished = true;
+        Ok(())
+    }
+
+    /// Discard the prepared version, leaving the ACL unchanged.
+    pub fn abort(mut self) -> Result<(), Error> {
+        self.finished = true;
+        self.clear()
+    }
+
+    fn clear(&self) -> Result<(), Error> {
+        self.builder
+            .connect()?
+            .acl_clear_version(self.id.clone(), self.version)
+    }
+}
+
+impl<B, T> Drop for AclTransaction<'_, B, T>
+where
+    B: ConnectionBuilder<Connection = Connection<T>>,
+    T: Read + Write,
+{
+    fn drop(&mut self) {
+        if !self.finished {
+            // Errors cannot be reported from `drop`; use `abort` to observe them.
+            let _ = self.clear();
+        }
+    }
+}
+
+/// A prepared version of a map which replaces the current contents when committed.
+///
+/// If the transaction is dropped without calling [`MapTransaction::commit`], the prepared version
+/// is cleared and the map is left unchanged.
+///
+/// # Examples
+/// ```no_run
+/// use haptik::UnixSocketBuilder;
+/// use haptik::models::MapId;
+/// use haptik::transaction::MapTransaction;
+///
+/// let socket_builder = UnixSocketBuilder::default();
+/// let mut transaction =
+///     MapTransaction::begin(&socket_builder, MapId::Id(2)).expect("Failed to prepare map");
+/// transaction.add("example.com", "server1").expect("Failed to add entry");
+/// transaction.add("example.org", "server2").expect("Failed to add entry");
+/// transaction.commit().expect("Failed to commit map");
+/// ```
+pub struct MapTransaction<'a, B, T>
+where
+    B: ConnectionBuilder<Connection = Connection<T>>,
+    T: Read + Write,
+{
+    builder: &'a B,
+    id: MapId,
+    version: u32,
+    finished: bool,
+    transport: PhantomData<T>,
+}
+
+impl<'a, B, T> MapTransaction<'a, B, T>
+where
+    B: ConnectionBuilder<Connection = Connection<T>>,
+    T: Read + Write,
+{
+    /// Prepare a new, empty version of the map.
+    pub fn begin(builder: &'a B, id: MapId) -> Result<Self, Error> {
+        let version = builder.connect()?.map_prepare(id.clone())?;
+
+        Ok(Self {
+            builder,
+            id,
+            version,
+            finished: false,
+            transport: PhantomData,
+        })
+    }
+
+    /// The version number HAProxy assigned to this transaction.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Add an entry to the prepared version.
+    pub fn add<K: ToString, V: ToString>(&mut self, key: K, value: V) -> Result<(), Error> {
+        self.builder
+            .connect()?
+            .map_add_version(self.id.clone(), self.version, key, value)
+    }
+
+    /// Replace the contents of the map with the prepared version.
+    ///
+    /// If the commit fails, the prepared version is cleared when the transaction is dropped.
+    pub fn commit(mut self) -> Result<(), Error> {
+        self.builder
+            .connect()?
+            .map_commit(self.id.clone(), self.version)?;
+        self.finished = true;
+        Ok(())
+    }
+
+    /// Discard the prepared version, leaving the map unchanged.
+    pub fn abort(mut self) -> Result<(), Error> {
+        self.finished = true;
+        self.clear()
+    }
+
+    fn clear(&self) -> Result<(), Error> {
+        self.builder
+            .connect()?
+            .map_clear_version(self.id.clone(), self.version)
+    }
+}
+
+impl<B, T> Drop for MapTransaction<'_, B, T>
+where
+    B: ConnectionBuilder<Connection = Connection<T>>,
+    T: Read + Write,
+{
+    fn drop(&mut self) {
+        if !self.finished {
+            // Errors cannot be reported from `drop`; use `abort` to observe them.
+            let _ = self.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockBuilder;
+
+    #[test]
+    fn failed_commit_clears_version() {
+        let builder = MockBuilder::new(&[
+            b"New version created: 3\n\n",
+            b"No such version.\n\n",
+            b"\n",
+        ]);
+        let transaction = AclTransaction::begin(&builder, AclId::Id(0)).unwrap();
+        assert!(transaction.commit().is_err());
+
+        assert_eq!(
+            builder.output.borrow().as_slice(),
+            &b"prepare acl #0\ncommit acl @3 #0\nclear acl @3 #0\n"[..]
+        );
+    }
+
+    #[test]
+    fn successful_commit_keeps_version() {
+        let builder = MockBuilder::new(&[b"New version created: 3\n\n", b"\n"]);
+        let transaction = MapTransaction::begin(&builder, MapId::Id(1)).unwrap();
+        transaction.commit().unwrap();
+
+        assert_eq!(
+            builder.output.borrow().as_slice(),
+            &b"prepare map #1\ncommit map @3 #1\n"[..]
+        );
+    }
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "async")]
+
+use haptik::models::AclId;
+use haptik::requests::EntryRef;
+use haptik::{AsyncConnectionBuilder, UnixSocketBuilder};
+
+#[tokio::test]
+#[ignore]
+async fn async_connection_acl_list() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let acls = builder
+        .connect_async()
+        .await
+        .unwrap()
+        .acl_list()
+        .await
+        .unwrap();
+    assert_eq!(acls.len(), 4);
+}
+
+#[tokio::test]
+#[ignore]
+async fn async_connection_acl_get() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let acl_match = builder
+        .connect_async()
+        .await
+        .unwrap()
+        .acl_get(AclId::Id(1000), "127.0.0.1")
+        .await;
+    assert!(matches!(acl_match, Err(haptik::errors::Error::UnknownId)));
+}
+
+#[tokio::test]
+#[ignore]
+async fn async_connection_spawned_map_updates() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let map_id = builder
+        .connect_async()
+        .await
+        .unwrap()
+        .map_resolve("/usr/local/etc/haproxy/hosts.map")
+        .await
+        .unwrap();
+
+    let tasks: Vec<_> = (1..=10)
+        .map(|n| {
+            let builder = builder.clone();
+            let map_id = map_id.clone();
+            tokio::spawn(async move {
+                builder
+                    .connect_async()
+                    .await?
+                    .map_add(map_id, format!("async-{}.example", n), "server1")
+                    .await
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    for n in 1..=10 {
+        let key = format!("async-{}.example", n);
+        builder
+            .connect_async()
+            .await
+            .unwrap()
+            .map_del(map_id.clone(), EntryRef::Key(&key))
+            .await
+            .unwrap();
+    }
+}
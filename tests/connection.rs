@@ -1,8 +1,16 @@
+use std::collections::HashSet;
 use std::net::{Ipv4Addr, SocketAddrV4};
 
-use haptik::models::AclId;
-use haptik::requests::{BackendId, ErrorFlag};
+use haptik::batch::Batch;
+use haptik::lifecycle::remove_server;
+use haptik::models::{AclId, MapId, StickTableKey, StickTableType};
+use haptik::requests::{
+    BackendId, DataFilter, EntryRef, ErrorFlag, RateLimit, ServerId, ServerSpec, ServerState,
+    StatFilter, StatObjects, TableFilter, Weight,
+};
 use haptik::responses;
+use haptik::sync::sync_acl;
+use haptik::transaction::AclTransaction;
 use haptik::{ConnectionBuilder, TcpSocketBuilder, UnixSocketBuilder};
 
 #[test]
@@ -34,11 +42,13 @@ fn connection_acl_add() {
 
     let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
     let connection = builder.connect().unwrap();
-    connection.acl_add(acl_id, &ip).unwrap();
+    connection.acl_add(acl_id.clone(), ip).unwrap();
 
     // Check that the ACL contains the new entry
     let connection = builder.connect().unwrap();
-    let acl_data = connection.acl_data::<std::net::Ipv4Addr>(acl_id).unwrap();
+    let acl_data = connection
+        .acl_data::<std::net::Ipv4Addr>(acl_id.clone())
+        .unwrap();
     assert!(acl_data.iter().any(|entry| entry.value == ip))
 }
 
@@ -72,6 +82,64 @@ fn connection_acl_data_str() {
     assert_eq!(acl_data[1].value, "127.0.0.2",);
 }
 
+#[test]
+#[ignore]
+fn connection_acl_get() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let connection = builder.connect().unwrap();
+    let acl_match = connection
+        .acl_get(AclId::Id(0), Ipv4Addr::new(127, 0, 0, 2))
+        .unwrap();
+    assert!(acl_match.matched);
+    assert_eq!(acl_match.pattern, Some("127.0.0.2".into()));
+
+    let connection = builder.connect().unwrap();
+    let acl_match = connection
+        .acl_get(AclId::Id(0), Ipv4Addr::new(10, 0, 0, 1))
+        .unwrap();
+    assert!(!acl_match.matched);
+}
+
+#[test]
+#[ignore]
+fn connection_acl_del() {
+    let ip = Ipv4Addr::new(192, 0, 2, 1);
+    let acl_id = AclId::Id(1);
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+
+    builder
+        .connect()
+        .unwrap()
+        .acl_add(acl_id.clone(), ip)
+        .unwrap();
+    let entry = builder
+        .connect()
+        .unwrap()
+        .acl_data::<Ipv4Addr>(acl_id.clone())
+        .unwrap()
+        .into_iter()
+        .find(|entry| entry.value == ip)
+        .unwrap();
+
+    builder
+        .connect()
+        .unwrap()
+        .acl_del(acl_id.clone(), EntryRef::Pointer(entry.id))
+        .unwrap();
+    let acl_data = builder
+        .connect()
+        .unwrap()
+        .acl_data::<Ipv4Addr>(acl_id.clone())
+        .unwrap();
+    assert!(!acl_data.iter().any(|entry| entry.value == ip));
+
+    assert!(builder
+        .connect()
+        .unwrap()
+        .acl_del(acl_id.clone(), EntryRef::Key("192.0.2.1"))
+        .is_err());
+}
+
 #[test]
 #[ignore]
 fn connection_acl_list() {
@@ -79,7 +147,7 @@ fn connection_acl_list() {
     let connection = builder.connect().unwrap();
     let acls = connection.acl_list().unwrap();
 
-    assert_eq!(acls.len(), 2);
+    assert_eq!(acls.len(), 4);
 
     assert_eq!(acls[0].id, 0);
     assert_eq!(acls[0].reference, None);
@@ -93,6 +161,18 @@ fn connection_acl_list() {
         acls[1].description,
         "acl 'src' file '/usr/local/etc/haproxy/haproxy.cfg' line 21"
     );
+    assert_eq!(acls[2].id, 2);
+    assert_eq!(acls[2].reference, None);
+    assert_eq!(
+        acls[2].description,
+        "acl 'src' file '/usr/local/etc/haproxy/haproxy.cfg' line 22"
+    );
+    assert_eq!(acls[3].id, 3);
+    assert_eq!(acls[3].reference, None);
+    assert_eq!(
+        acls[3].description,
+        "acl 'src' file '/usr/local/etc/haproxy/haproxy.cfg' line 23"
+    );
 }
 
 #[test]
@@ -170,3 +250,845 @@ fn connection_errors_backend_id() {
         0
     );
 }
+
+#[test]
+#[ignore]
+fn connection_stats() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let connection = builder.connect().unwrap();
+    let stats = connection.stats().unwrap();
+
+    assert!(stats
+        .iter()
+        .any(|row| row.pxname == "http-in" && row.svname == "FRONTEND"));
+    assert!(stats
+        .iter()
+        .any(|row| row.pxname == "servers" && row.svname == "server1"));
+    assert!(stats
+        .iter()
+        .any(|row| row.pxname == "servers" && row.svname == "BACKEND"));
+}
+
+#[test]
+#[ignore]
+fn connection_stats_filtered() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let connection = builder.connect().unwrap();
+    let stats = connection
+        .stats_filtered(StatFilter {
+            proxy: BackendId::Name("servers"),
+            objects: StatObjects::SERVER,
+            server: None,
+        })
+        .unwrap();
+
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].pxname, "servers");
+    assert_eq!(stats[0].svname, "server1");
+}
+
+#[test]
+#[ignore]
+fn connection_info() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let connection = builder.connect().unwrap();
+    let info = connection.info().unwrap();
+
+    assert_eq!(info.name, Some("HAProxy".into()));
+    assert!(info.version.is_some());
+    assert_eq!(info.maxconn, Some(256));
+    assert_eq!(info.stopping, Some(false));
+}
+
+#[cfg(feature = "json")]
+#[test]
+#[ignore]
+fn connection_stats_json() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let connection = builder.connect().unwrap();
+    let stats = connection.stats_json().unwrap();
+
+    let frontend = stats
+        .iter()
+        .find(|row| row.value.pxname == "http-in" && row.value.svname == "FRONTEND")
+        .unwrap();
+    assert_eq!(
+        frontend.tags["stot"].nature,
+        responses::FieldNature::Counter
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+#[ignore]
+fn connection_info_json() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let connection = builder.connect().unwrap();
+    let info = connection.info_json().unwrap();
+
+    assert_eq!(info.value.name, Some("HAProxy".into()));
+    assert_eq!(info.value.maxconn, Some(256));
+}
+
+#[test]
+#[ignore]
+fn connection_stats_typed() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let connection = builder.connect().unwrap();
+    let fields = connection.stats_typed().unwrap();
+
+    assert!(fields.iter().any(|field| field.name == "pxname"
+        && field.value == responses::TypedField::Str("http-in".into())));
+}
+
+#[test]
+#[ignore]
+fn connection_info_typed() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let connection = builder.connect().unwrap();
+    let fields = connection.info_typed().unwrap();
+
+    assert!(fields
+        .iter()
+        .any(|field| field.name == "Name"
+            && field.value == responses::TypedField::Str("HAProxy".into())));
+}
+
+fn hosts_map_id(builder: &UnixSocketBuilder) -> MapId {
+    let maps = builder.connect().unwrap().map_list().unwrap();
+    let map = maps
+        .iter()
+        .find(|map| {
+            map.reference
+                .as_ref()
+                .is_some_and(|reference| reference.ends_with("hosts.map"))
+        })
+        .expect("Failed to find hosts.map");
+    MapId::Id(map.id)
+}
+
+#[test]
+#[ignore]
+fn connection_map_entries() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let map_id = hosts_map_id(&builder);
+    let entries = builder
+        .connect()
+        .unwrap()
+        .map_entries::<String, String>(map_id.clone())
+        .unwrap();
+
+    assert!(entries
+        .iter()
+        .any(|entry| entry.key == "example.com" && entry.value == "server1"));
+}
+
+#[test]
+#[ignore]
+fn connection_map_get() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let map_id = hosts_map_id(&builder);
+
+    let map_match = builder
+        .connect()
+        .unwrap()
+        .map_get(map_id.clone(), "example.org")
+        .unwrap();
+    assert!(map_match.found);
+    assert_eq!(map_match.value, Some("server1".into()));
+
+    let map_match = builder
+        .connect()
+        .unwrap()
+        .map_get(map_id.clone(), "unknown.example")
+        .unwrap();
+    assert!(!map_match.found);
+}
+
+#[test]
+#[ignore]
+fn connection_map_add_set_del() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let map_id = hosts_map_id(&builder);
+
+    builder
+        .connect()
+        .unwrap()
+        .map_add(map_id.clone(), "add-set-del.example", "server1")
+        .unwrap();
+    builder
+        .connect()
+        .unwrap()
+        .map_set(
+            map_id.clone(),
+            EntryRef::Key("add-set-del.example"),
+            "server2",
+        )
+        .unwrap();
+    let map_match = builder
+        .connect()
+        .unwrap()
+        .map_get(map_id.clone(), "add-set-del.example")
+        .unwrap();
+    assert_eq!(map_match.value, Some("server2".into()));
+
+    builder
+        .connect()
+        .unwrap()
+        .map_del(map_id.clone(), EntryRef::Key("add-set-del.example"))
+        .unwrap();
+    let map_match = builder
+        .connect()
+        .unwrap()
+        .map_get(map_id.clone(), "add-set-del.example")
+        .unwrap();
+    assert!(!map_match.found);
+}
+
+#[test]
+#[ignore]
+fn connection_map_resolve() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let map_id = builder
+        .connect()
+        .unwrap()
+        .map_resolve("/usr/local/etc/haproxy/hosts.map")
+        .unwrap();
+    assert_eq!(map_id, hosts_map_id(&builder));
+
+    assert!(matches!(
+        builder
+            .connect()
+            .unwrap()
+            .map_resolve("/usr/local/etc/haproxy/missing.map"),
+        Err(haptik::errors::Error::UnknownId)
+    ));
+}
+
+#[test]
+#[ignore]
+fn connection_map_entries_by_file() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let entries = builder
+        .connect()
+        .unwrap()
+        .map_entries::<String, String>(MapId::File("/usr/local/etc/haproxy/hosts.map".into()))
+        .unwrap();
+
+    assert!(entries.iter().any(|entry| entry.key == "example.com"));
+}
+
+#[test]
+#[ignore]
+fn connection_acl_transaction() {
+    let acl_id = AclId::Id(2);
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+
+    // Dropping an uncommitted transaction leaves the ACL unchanged.
+    let mut transaction = AclTransaction::begin(&builder, acl_id.clone()).unwrap();
+    transaction.add(Ipv4Addr::new(203, 0, 113, 1)).unwrap();
+    drop(transaction);
+    let acl_data = builder
+        .connect()
+        .unwrap()
+        .acl_data::<Ipv4Addr>(acl_id.clone())
+        .unwrap();
+    assert!(!acl_data
+        .iter()
+        .any(|entry| entry.value == Ipv4Addr::new(203, 0, 113, 1)));
+
+    let mut transaction = AclTransaction::begin(&builder, acl_id.clone()).unwrap();
+    transaction.add(Ipv4Addr::new(198, 51, 100, 1)).unwrap();
+    transaction.add(Ipv4Addr::new(198, 51, 100, 2)).unwrap();
+    transaction.commit().unwrap();
+    let acl_data = builder
+        .connect()
+        .unwrap()
+        .acl_data::<Ipv4Addr>(acl_id)
+        .unwrap();
+    assert_eq!(
+        acl_data.iter().map(|entry| entry.value).collect::<Vec<_>>(),
+        vec![
+            Ipv4Addr::new(198, 51, 100, 1),
+            Ipv4Addr::new(198, 51, 100, 2)
+        ]
+    );
+}
+
+#[test]
+#[ignore]
+fn connection_sync_acl() {
+    let acl_id = AclId::Id(3);
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let desired: HashSet<Ipv4Addr> = vec![Ipv4Addr::new(192, 0, 2, 2), Ipv4Addr::new(192, 0, 2, 3)]
+        .into_iter()
+        .collect();
+
+    let report = sync_acl(&builder, acl_id.clone(), &desired).unwrap();
+    assert_eq!(report.added, vec![Ipv4Addr::new(192, 0, 2, 3)]);
+    assert!(!report.removed.is_empty());
+    assert!(report
+        .removed
+        .iter()
+        .all(|entry| entry.value == Ipv4Addr::new(192, 0, 2, 1)));
+
+    let acl_data = builder
+        .connect()
+        .unwrap()
+        .acl_data::<Ipv4Addr>(acl_id.clone())
+        .unwrap();
+    assert_eq!(
+        acl_data
+            .iter()
+            .map(|entry| entry.value)
+            .collect::<HashSet<_>>(),
+        desired
+    );
+
+    // A second synchronization has nothing to do.
+    assert!(sync_acl(&builder, acl_id, &desired).unwrap().is_unchanged());
+}
+
+fn server1_status(builder: &UnixSocketBuilder) -> Option<String> {
+    let stats = builder
+        .connect()
+        .unwrap()
+        .stats_filtered(StatFilter {
+            proxy: BackendId::Name("servers"),
+            objects: StatObjects::SERVER,
+            server: None,
+        })
+        .unwrap();
+    stats[0].status.clone()
+}
+
+#[test]
+#[ignore]
+fn connection_set_server_state() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+
+    builder
+        .connect()
+        .unwrap()
+        .set_server_state(
+            BackendId::Name("servers"),
+            ServerId::Name("server1"),
+            ServerState::Drain,
+        )
+        .unwrap();
+    assert_eq!(server1_status(&builder), Some("DRAIN".into()));
+
+    builder
+        .connect()
+        .unwrap()
+        .disable_server(BackendId::Name("servers"), ServerId::Name("server1"))
+        .unwrap();
+    assert_eq!(server1_status(&builder), Some("MAINT".into()));
+
+    builder
+        .connect()
+        .unwrap()
+        .enable_server(BackendId::Name("servers"), ServerId::Name("server1"))
+        .unwrap();
+    builder
+        .connect()
+        .unwrap()
+        .set_server_state(
+            BackendId::Name("servers"),
+            ServerId::Name("server1"),
+            ServerState::Ready,
+        )
+        .unwrap();
+    assert_ne!(server1_status(&builder), Some("DRAIN".into()));
+    assert_ne!(server1_status(&builder), Some("MAINT".into()));
+
+    assert!(matches!(
+        builder.connect().unwrap().set_server_state(
+            BackendId::Name("servers"),
+            ServerId::Name("missing"),
+            ServerState::Ready,
+        ),
+        Err(haptik::errors::Error::UnknownId)
+    ));
+}
+
+#[test]
+#[ignore]
+fn connection_set_server_weight() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+
+    builder
+        .connect()
+        .unwrap()
+        .set_server_weight(
+            BackendId::Name("servers"),
+            ServerId::Name("server1"),
+            Weight::Absolute(10),
+        )
+        .unwrap();
+    builder
+        .connect()
+        .unwrap()
+        .set_server_weight(
+            BackendId::Name("servers"),
+            ServerId::Name("server1"),
+            Weight::Percent(100),
+        )
+        .unwrap();
+
+    assert!(matches!(
+        builder.connect().unwrap().set_server_weight(
+            BackendId::Name("servers"),
+            ServerId::Name("server1"),
+            Weight::Absolute(1000),
+        ),
+        Err(haptik::errors::Error::InvalidArgument)
+    ));
+}
+
+#[test]
+#[ignore]
+fn connection_set_server_addr() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+
+    let change = builder
+        .connect()
+        .unwrap()
+        .set_server_addr(
+            BackendId::Name("servers"),
+            ServerId::Name("server1"),
+            Ipv4Addr::new(127, 0, 0, 2).into(),
+            Some(8001),
+        )
+        .unwrap();
+    assert_eq!(change.old_addr, Some(Ipv4Addr::new(127, 0, 0, 1).into()));
+    assert_eq!(change.new_addr, Some(Ipv4Addr::new(127, 0, 0, 2).into()));
+    assert_eq!(change.old_port, Some(8000));
+    assert_eq!(change.new_port, Some(8001));
+
+    let change = builder
+        .connect()
+        .unwrap()
+        .set_server_addr(
+            BackendId::Name("servers"),
+            ServerId::Name("server1"),
+            Ipv4Addr::new(127, 0, 0, 1).into(),
+            Some(8000),
+        )
+        .unwrap();
+    assert_eq!(change.new_addr, Some(Ipv4Addr::new(127, 0, 0, 1).into()));
+}
+
+#[test]
+#[ignore]
+fn connection_add_del_server() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let spec = ServerSpec::new("127.0.0.1:8002".parse().unwrap())
+        .weight(5)
+        .check()
+        .maxconn(10);
+
+    builder
+        .connect()
+        .unwrap()
+        .add_server(BackendId::Name("servers"), "dynamic1", &spec)
+        .unwrap();
+    builder
+        .connect()
+        .unwrap()
+        .enable_health(BackendId::Name("servers"), ServerId::Name("dynamic1"))
+        .unwrap();
+    builder
+        .connect()
+        .unwrap()
+        .disable_health(BackendId::Name("servers"), ServerId::Name("dynamic1"))
+        .unwrap();
+
+    // Servers which are in service cannot be deleted.
+    builder
+        .connect()
+        .unwrap()
+        .set_server_state(
+            BackendId::Name("servers"),
+            ServerId::Name("dynamic1"),
+            ServerState::Ready,
+        )
+        .unwrap();
+    assert!(matches!(
+        builder
+            .connect()
+            .unwrap()
+            .del_server(BackendId::Name("servers"), ServerId::Name("dynamic1")),
+        Err(haptik::errors::Error::InvalidState)
+    ));
+    assert!(matches!(
+        remove_server(
+            &builder,
+            BackendId::Name("servers"),
+            ServerId::Name("dynamic1")
+        ),
+        Err(haptik::errors::Error::InvalidState)
+    ));
+
+    builder
+        .connect()
+        .unwrap()
+        .set_server_state(
+            BackendId::Name("servers"),
+            ServerId::Name("dynamic1"),
+            ServerState::Maint,
+        )
+        .unwrap();
+    builder
+        .connect()
+        .unwrap()
+        .del_server(BackendId::Name("servers"), ServerId::Name("dynamic1"))
+        .unwrap();
+
+    assert!(matches!(
+        builder
+            .connect()
+            .unwrap()
+            .del_server(BackendId::Name("servers"), ServerId::Name("dynamic1")),
+        Err(haptik::errors::Error::UnknownId)
+    ));
+}
+
+#[test]
+#[ignore]
+fn connection_servers_state() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+
+    let rows = builder
+        .connect()
+        .unwrap()
+        .servers_state(Some(BackendId::Name("servers")))
+        .unwrap();
+    let server1 = rows
+        .iter()
+        .find(|row| row.srv_name == "server1")
+        .expect("server1 missing from server state");
+    assert_eq!(server1.be_name, "servers");
+    assert_eq!(server1.srv_addr, Some(Ipv4Addr::new(127, 0, 0, 1).into()));
+    assert_eq!(server1.srv_port, Some(8000));
+
+    let all = builder.connect().unwrap().servers_state(None).unwrap();
+    assert!(all.len() >= rows.len());
+
+    assert!(matches!(
+        builder
+            .connect()
+            .unwrap()
+            .servers_state(Some(BackendId::Name("missing"))),
+        Err(haptik::errors::Error::UnknownId)
+    ));
+}
+
+#[test]
+#[ignore]
+fn connection_backends() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+
+    let backends = builder.connect().unwrap().backends().unwrap();
+    assert!(backends.iter().any(|name| name == "servers"));
+}
+
+#[test]
+#[ignore]
+fn connection_servers_conn() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+
+    let rows = builder
+        .connect()
+        .unwrap()
+        .servers_conn(Some(BackendId::Name("servers")))
+        .unwrap();
+    let server1 = rows
+        .iter()
+        .find(|row| row.srv_name == "server1")
+        .expect("server1 missing from connection counters");
+    assert_eq!(server1.be_name, "servers");
+    assert!(!server1.idle_per_thread.is_empty());
+}
+
+#[test]
+#[ignore]
+fn connection_sessions() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+
+    // The CLI session running the command is always listed.
+    let sessions = builder.connect().unwrap().sessions().unwrap();
+    let cli = sessions
+        .iter()
+        .find(|session| session.proto.as_deref() == Some("unix_stream"))
+        .expect("CLI session missing");
+
+    // The CLI session has ended by the time the next connection asks about it.
+    assert!(matches!(
+        builder.connect().unwrap().session(cli.ptr),
+        Err(haptik::errors::Error::NotFound)
+    ));
+    assert!(matches!(
+        builder.connect().unwrap().shutdown_session(cli.ptr),
+        Err(haptik::errors::Error::UnknownId)
+    ));
+}
+
+#[test]
+#[ignore]
+fn connection_disable_enable_frontend() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+
+    assert_eq!(
+        builder
+            .connect()
+            .unwrap()
+            .disable_frontend("http-in")
+            .unwrap(),
+        responses::StateChange::Applied
+    );
+    assert_eq!(
+        builder
+            .connect()
+            .unwrap()
+            .disable_frontend("http-in")
+            .unwrap(),
+        responses::StateChange::Unchanged
+    );
+    assert_eq!(
+        builder
+            .connect()
+            .unwrap()
+            .enable_frontend("http-in")
+            .unwrap(),
+        responses::StateChange::Applied
+    );
+    assert_eq!(
+        builder
+            .connect()
+            .unwrap()
+            .enable_frontend("http-in")
+            .unwrap(),
+        responses::StateChange::Unchanged
+    );
+}
+
+#[test]
+#[ignore]
+fn connection_shutdown() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+
+    builder
+        .connect()
+        .unwrap()
+        .shutdown_sessions_server(BackendId::Name("servers"), ServerId::Name("server1"))
+        .unwrap();
+
+    // Shutting down a frontend cannot be undone, so only check that unknown names are reported.
+    assert!(matches!(
+        builder.connect().unwrap().shutdown_frontend("missing"),
+        Err(haptik::errors::Error::UnknownId)
+    ));
+}
+
+#[test]
+#[ignore]
+fn connection_tables() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+
+    let tables = builder.connect().unwrap().tables().unwrap();
+    let table = tables
+        .iter()
+        .find(|table| table.name == "http-in")
+        .expect("http-in table missing");
+    assert_eq!(table.table_type, StickTableType::Ip);
+    assert_eq!(table.size, 1024);
+}
+
+#[test]
+#[ignore]
+fn connection_table_entries() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+
+    let filter = TableFilter::Data(vec![DataFilter::ge("gpc0", 0)]);
+    for entry in builder
+        .connect()
+        .unwrap()
+        .table_entries("http-in", Some(&filter))
+        .unwrap()
+    {
+        assert!(entry.data.contains_key("gpc0"));
+        assert!(entry.data.contains_key("conn_rate"));
+    }
+
+    assert!(matches!(
+        builder.connect().unwrap().table_entries("missing", None),
+        Err(haptik::errors::Error::UnknownId)
+    ));
+    assert!(matches!(
+        builder
+            .connect()
+            .unwrap()
+            .table_entries("http-in", Some(&TableFilter::Data(Vec::new()))),
+        Err(haptik::errors::Error::InvalidArgument)
+    ));
+}
+
+#[test]
+#[ignore]
+fn connection_table_set_clear() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let key = StickTableKey::Ip(Ipv4Addr::new(192, 0, 2, 9));
+    let filter = TableFilter::Key(key.clone());
+
+    builder
+        .connect()
+        .unwrap()
+        .table_set("http-in", &key, &[("gpc0", 5)])
+        .unwrap();
+    let entries = builder
+        .connect()
+        .unwrap()
+        .table_entries("http-in", Some(&filter))
+        .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].key, key);
+    assert_eq!(
+        entries[0].data["gpc0"],
+        responses::StickTableData::Unsigned(5)
+    );
+
+    builder
+        .connect()
+        .unwrap()
+        .table_clear("http-in", Some(&filter))
+        .unwrap();
+    assert!(builder
+        .connect()
+        .unwrap()
+        .table_entries("http-in", Some(&filter))
+        .unwrap()
+        .is_empty());
+
+    assert!(matches!(
+        builder
+            .connect()
+            .unwrap()
+            .table_set("http-in", &key, &[("data gpc0", 1)]),
+        Err(haptik::errors::Error::InvalidArgument)
+    ));
+}
+
+#[test]
+#[ignore]
+fn connection_set_maxconn() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+
+    builder.connect().unwrap().set_maxconn_global(128).unwrap();
+    builder.connect().unwrap().set_maxconn_global(256).unwrap();
+    assert!(matches!(
+        builder.connect().unwrap().set_maxconn_global(100_000),
+        Err(haptik::errors::Error::InvalidArgument)
+    ));
+    assert!(matches!(
+        builder.connect().unwrap().set_maxconn_global(u32::MAX),
+        Err(haptik::errors::Error::InvalidArgument)
+    ));
+
+    builder
+        .connect()
+        .unwrap()
+        .set_maxconn_frontend("http-in", 100)
+        .unwrap();
+    builder
+        .connect()
+        .unwrap()
+        .set_maxconn_server(BackendId::Name("servers"), ServerId::Name("server1"), 32)
+        .unwrap();
+}
+
+#[test]
+#[ignore]
+fn connection_set_rate_limit() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+
+    builder
+        .connect()
+        .unwrap()
+        .set_rate_limit(RateLimit::Connections, 1000)
+        .unwrap();
+    builder
+        .connect()
+        .unwrap()
+        .set_rate_limit(RateLimit::Connections, 0)
+        .unwrap();
+}
+
+#[test]
+#[ignore]
+fn connection_interactive() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let mut connection = builder.connect().unwrap().interactive().unwrap();
+
+    // Fill a prepared version of an ACL over one connection, then discard it.
+    let version = connection.command().acl_prepare(AclId::Id(0)).unwrap();
+    for host in 1..=50 {
+        connection
+            .command()
+            .acl_add_version(AclId::Id(0), version, Ipv4Addr::new(10, 0, 0, host))
+            .unwrap();
+    }
+    assert_eq!(connection.command().acl_list().unwrap().len(), 4);
+    connection
+        .command()
+        .acl_clear_version(AclId::Id(0), version)
+        .unwrap();
+
+    // Errors leave the connection usable.
+    assert!(matches!(
+        connection.command().acl_get(AclId::Id(1000), "127.0.0.1"),
+        Err(haptik::errors::Error::UnknownId)
+    ));
+    assert!(connection.command().level().is_ok());
+}
+
+#[test]
+#[ignore]
+fn connection_batch() {
+    let builder = UnixSocketBuilder::new("/tmp/socket/haproxy.sock");
+    let map_id = hosts_map_id(&builder);
+
+    let mut batch = Batch::new();
+    let added: Vec<_> = (1..=50)
+        .map(|n| batch.map_add(map_id.clone(), format!("batch-{}.example", n), "server1"))
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let found = batch.map_get(map_id.clone(), "batch-50.example").unwrap();
+    let missing = batch
+        .map_del(map_id.clone(), EntryRef::Key("missing.example"))
+        .unwrap();
+    let responses = builder.connect().unwrap().batch(&batch).unwrap();
+
+    assert_eq!(responses.len(), 52);
+    for pending in added.iter() {
+        responses.get(pending).unwrap();
+    }
+    assert_eq!(responses.get(&found).unwrap().value, Some("server1".into()));
+    assert!(matches!(
+        responses.get(&missing),
+        Err(haptik::errors::Error::NotFound)
+    ));
+
+    let mut batch = Batch::new();
+    let deleted: Vec<_> = (1..=50)
+        .map(|n| {
+            let key = format!("batch-{}.example", n);
+            batch.map_del(map_id.clone(), EntryRef::Key(&key))
+        })
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let responses = builder.connect().unwrap().batch(&batch).unwrap();
+    for pending in deleted.iter() {
+        responses.get(pending).unwrap();
+    }
+}